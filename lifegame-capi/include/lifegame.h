@@ -0,0 +1,118 @@
+/* C API of lifegame-core, generated by cbindgen. Do not edit. */
+
+#ifndef LIFEGAME_H
+#define LIFEGAME_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of the C API functions.
+typedef enum LifegameStatus {
+  LIFEGAME_STATUS_OK = 0,
+  // a pointer argument is null
+  LIFEGAME_STATUS_NULL_POINTER = 1,
+  // the size or the rule is invalid
+  LIFEGAME_STATUS_INVALID_ARGUMENT = 2,
+  // the cell is out of the world, or on its boundary for writing
+  LIFEGAME_STATUS_OUT_OF_RANGE = 3,
+  // the buffer is smaller than the world
+  LIFEGAME_STATUS_BUFFER_TOO_SMALL = 4,
+  // the engine panicked, which is a bug
+  LIFEGAME_STATUS_PANIC = 5,
+} LifegameStatus;
+
+// Opaque handle of a world, whose size includes the always-dead boundary.
+typedef struct LifegameWorld LifegameWorld;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create a world of dead cells with a rule like `B3/S23` or `/2/3`, or B3/S23 if `rule` is null.
+//
+// # Safety
+//
+// `rule` must be null or a NUL-terminated string, and `out` must be a valid pointer.
+// The world written to `out` must be released by [`lifegame_world_destroy`].
+enum LifegameStatus lifegame_world_create(size_t width,
+                                          size_t height,
+                                          const char *rule,
+                                          struct LifegameWorld **out);
+
+// Release the world. Null is ignored.
+//
+// # Safety
+//
+// `world` must be null or created by [`lifegame_world_create`], and not used afterwards.
+void lifegame_world_destroy(struct LifegameWorld *world);
+
+// Advance the world by the number of generations
+//
+// # Safety
+//
+// `world` must be null or a valid world.
+enum LifegameStatus lifegame_world_step(struct LifegameWorld *world, size_t generations);
+
+// Write the size including the boundary
+//
+// # Safety
+//
+// `world` must be null or a valid world, and `width` and `height` valid pointers.
+enum LifegameStatus lifegame_world_size(const struct LifegameWorld *world,
+                                        size_t *width,
+                                        size_t *height);
+
+// Write the generation
+//
+// # Safety
+//
+// `world` must be null or a valid world, and `out` a valid pointer.
+enum LifegameStatus lifegame_world_generation(const struct LifegameWorld *world, uint64_t *out);
+
+// Write the number of alive cells
+//
+// # Safety
+//
+// `world` must be null or a valid world, and `out` a valid pointer.
+enum LifegameStatus lifegame_world_population(const struct LifegameWorld *world, size_t *out);
+
+// Write the state of the cell, 0 for dead, 1 for alive and 2 or more for dying cells of Generations rules
+//
+// # Safety
+//
+// `world` must be null or a valid world, and `out` a valid pointer.
+enum LifegameStatus lifegame_world_get_cell(const struct LifegameWorld *world,
+                                            size_t ix,
+                                            size_t iy,
+                                            uint8_t *out);
+
+// Set the cell alive for nonzero `cell` or dead for 0. Cells on the boundary cannot be set.
+//
+// # Safety
+//
+// `world` must be null or a valid world.
+enum LifegameStatus lifegame_world_set_cell(struct LifegameWorld *world,
+                                            size_t ix,
+                                            size_t iy,
+                                            uint8_t cell);
+
+// Copy the present cells in row-major order, `width * height` bytes of the states like [`lifegame_world_get_cell`]
+//
+// # Safety
+//
+// `world` must be null or a valid world, and `buffer` null or valid for `length` bytes.
+enum LifegameStatus lifegame_world_copy_cells(const struct LifegameWorld *world,
+                                              uint8_t *buffer,
+                                              size_t length);
+
+// Static NUL-terminated description of the status
+const char *lifegame_status_message(enum LifegameStatus status);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* LIFEGAME_H */
@@ -0,0 +1,259 @@
+//! C ABI of lifegame-core for embedding the engine.
+//!
+//! The header `include/lifegame.h` is generated by cbindgen on build.
+//! Every function returning [`LifegameStatus`] checks its pointers, and never unwinds into C.
+
+use std::ffi::{c_char, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use lifegame_core::{Rule, World, CELL_ALIVE, CELL_DEAD};
+
+/// Result of the C API functions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifegameStatus {
+    Ok = 0,
+    /// a pointer argument is null
+    NullPointer = 1,
+    /// the size or the rule is invalid
+    InvalidArgument = 2,
+    /// the cell is out of the world, or on its boundary for writing
+    OutOfRange = 3,
+    /// the buffer is smaller than the world
+    BufferTooSmall = 4,
+    /// the engine panicked, which is a bug
+    Panic = 5,
+}
+
+/// Opaque handle of a world, whose size includes the always-dead boundary.
+pub struct LifegameWorld {
+    world: World,
+}
+
+/// Create a world of dead cells with a rule like `B3/S23` or `/2/3`, or B3/S23 if `rule` is null.
+///
+/// # Safety
+///
+/// `rule` must be null or a NUL-terminated string, and `out` must be a valid pointer.
+/// The world written to `out` must be released by [`lifegame_world_destroy`].
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_create(
+    width: usize,
+    height: usize,
+    rule: *const c_char,
+    out: *mut *mut LifegameWorld,
+) -> LifegameStatus {
+    if out.is_null() {
+        return LifegameStatus::NullPointer;
+    }
+    guard(|| {
+        let rule = match rule.is_null() {
+            true => Rule::default(),
+            false => match CStr::from_ptr(rule)
+                .to_str()
+                .ok()
+                .and_then(|r| r.parse().ok())
+            {
+                Some(rule) => rule,
+                None => return LifegameStatus::InvalidArgument,
+            },
+        };
+        let Some(size) = width.checked_mul(height) else {
+            return LifegameStatus::InvalidArgument;
+        };
+        let world = match World::new(width, height, &vec![CELL_DEAD; size]) {
+            Ok(world) => world.with_rule(rule),
+            Err(_) => return LifegameStatus::InvalidArgument,
+        };
+        *out = Box::into_raw(Box::new(LifegameWorld { world }));
+        LifegameStatus::Ok
+    })
+}
+
+/// Release the world. Null is ignored.
+///
+/// # Safety
+///
+/// `world` must be null or created by [`lifegame_world_create`], and not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_destroy(world: *mut LifegameWorld) {
+    if !world.is_null() {
+        drop(Box::from_raw(world));
+    }
+}
+
+/// Advance the world by the number of generations
+///
+/// # Safety
+///
+/// `world` must be null or a valid world.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_step(
+    world: *mut LifegameWorld,
+    generations: usize,
+) -> LifegameStatus {
+    let Some(world) = world.as_mut() else {
+        return LifegameStatus::NullPointer;
+    };
+    guard(|| {
+        for _ in 0..generations {
+            world.world.next();
+        }
+        LifegameStatus::Ok
+    })
+}
+
+/// Write the size including the boundary
+///
+/// # Safety
+///
+/// `world` must be null or a valid world, and `width` and `height` valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_size(
+    world: *const LifegameWorld,
+    width: *mut usize,
+    height: *mut usize,
+) -> LifegameStatus {
+    let Some(world) = world.as_ref() else {
+        return LifegameStatus::NullPointer;
+    };
+    if width.is_null() || height.is_null() {
+        return LifegameStatus::NullPointer;
+    }
+    *width = world.world.nx();
+    *height = world.world.ny();
+    LifegameStatus::Ok
+}
+
+/// Write the generation
+///
+/// # Safety
+///
+/// `world` must be null or a valid world, and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_generation(
+    world: *const LifegameWorld,
+    out: *mut u64,
+) -> LifegameStatus {
+    match (world.as_ref(), out.as_mut()) {
+        (Some(world), Some(out)) => {
+            *out = world.world.generation() as u64;
+            LifegameStatus::Ok
+        }
+        _ => LifegameStatus::NullPointer,
+    }
+}
+
+/// Write the number of alive cells
+///
+/// # Safety
+///
+/// `world` must be null or a valid world, and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_population(
+    world: *const LifegameWorld,
+    out: *mut usize,
+) -> LifegameStatus {
+    match (world.as_ref(), out.as_mut()) {
+        (Some(world), Some(out)) => {
+            *out = world.world.population();
+            LifegameStatus::Ok
+        }
+        _ => LifegameStatus::NullPointer,
+    }
+}
+
+/// Write the state of the cell, 0 for dead, 1 for alive and 2 or more for dying cells of Generations rules
+///
+/// # Safety
+///
+/// `world` must be null or a valid world, and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_get_cell(
+    world: *const LifegameWorld,
+    ix: usize,
+    iy: usize,
+    out: *mut u8,
+) -> LifegameStatus {
+    let (Some(world), Some(out)) = (world.as_ref(), out.as_mut()) else {
+        return LifegameStatus::NullPointer;
+    };
+    if ix >= world.world.nx() || iy >= world.world.ny() {
+        return LifegameStatus::OutOfRange;
+    }
+    *out = world.world.get_present_cell(ix, iy);
+    LifegameStatus::Ok
+}
+
+/// Set the cell alive for nonzero `cell` or dead for 0. Cells on the boundary cannot be set.
+///
+/// # Safety
+///
+/// `world` must be null or a valid world.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_set_cell(
+    world: *mut LifegameWorld,
+    ix: usize,
+    iy: usize,
+    cell: u8,
+) -> LifegameStatus {
+    let Some(world) = world.as_mut() else {
+        return LifegameStatus::NullPointer;
+    };
+    if !world.world.is_inner(ix, iy) {
+        return LifegameStatus::OutOfRange;
+    }
+    let cell = match cell {
+        CELL_DEAD => CELL_DEAD,
+        _ => CELL_ALIVE,
+    };
+    guard(|| {
+        world.world.set_present_cell(ix, iy, cell);
+        LifegameStatus::Ok
+    })
+}
+
+/// Copy the present cells in row-major order, `width * height` bytes of the states like [`lifegame_world_get_cell`]
+///
+/// # Safety
+///
+/// `world` must be null or a valid world, and `buffer` null or valid for `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn lifegame_world_copy_cells(
+    world: *const LifegameWorld,
+    buffer: *mut u8,
+    length: usize,
+) -> LifegameStatus {
+    let Some(world) = world.as_ref() else {
+        return LifegameStatus::NullPointer;
+    };
+    if buffer.is_null() {
+        return LifegameStatus::NullPointer;
+    }
+    let cells = world.world.present_cells();
+    if length < cells.len() {
+        return LifegameStatus::BufferTooSmall;
+    }
+    ptr::copy_nonoverlapping(cells.as_ptr(), buffer, cells.len());
+    LifegameStatus::Ok
+}
+
+/// Static NUL-terminated description of the status
+#[no_mangle]
+pub extern "C" fn lifegame_status_message(status: LifegameStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        LifegameStatus::Ok => b"ok\0",
+        LifegameStatus::NullPointer => b"null pointer\0",
+        LifegameStatus::InvalidArgument => b"invalid argument\0",
+        LifegameStatus::OutOfRange => b"cell out of range\0",
+        LifegameStatus::BufferTooSmall => b"buffer too small\0",
+        LifegameStatus::Panic => b"engine panicked\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Run `f`, turning a panic into [`LifegameStatus::Panic`]
+fn guard(f: impl FnOnce() -> LifegameStatus) -> LifegameStatus {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(LifegameStatus::Panic)
+}
@@ -0,0 +1,38 @@
+//! Build `test_capi.c` with the C compiler (`$CC` or `cc`) against the static library and run it.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory of the library artifacts, the parent of `deps` holding this test
+fn target_dir() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    exe.parent().and_then(Path::parent).unwrap().to_path_buf()
+}
+
+#[test]
+fn test_c_program() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = target_dir();
+    let library = target_dir.join("deps").join("liblifegame_capi.a");
+    assert!(library.exists(), "{} is not built", library.display());
+    let program = target_dir.join("test_capi");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg(crate_dir.join("tests").join("test_capi.c"))
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .expect("cannot run the C compiler");
+    assert!(status.success(), "cannot compile test_capi.c");
+
+    let output = Command::new(&program).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "test_capi failed:\n{}", stderr);
+}
@@ -0,0 +1,76 @@
+/* Exercise the C API of lifegame-core: build with the static library and run, exiting 0 on success. */
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "lifegame.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                  \
+  do {                                                                    \
+    if (!(condition)) {                                                   \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+      failures++;                                                         \
+    }                                                                     \
+  } while (0)
+
+#define CHECK_STATUS(call, expected) CHECK((call) == (expected))
+
+int main(void) {
+  LifegameWorld *world = NULL;
+  CHECK_STATUS(lifegame_world_create(7, 6, "B3/S23", &world), LIFEGAME_STATUS_OK);
+  CHECK(world != NULL);
+
+  size_t width = 0, height = 0;
+  CHECK_STATUS(lifegame_world_size(world, &width, &height), LIFEGAME_STATUS_OK);
+  CHECK(width == 7 && height == 6);
+
+  /* a blinker */
+  for (size_t ix = 2; ix <= 4; ix++) {
+    CHECK_STATUS(lifegame_world_set_cell(world, ix, 3, 1), LIFEGAME_STATUS_OK);
+  }
+  size_t population = 0;
+  CHECK_STATUS(lifegame_world_population(world, &population), LIFEGAME_STATUS_OK);
+  CHECK(population == 3);
+
+  CHECK_STATUS(lifegame_world_step(world, 1), LIFEGAME_STATUS_OK);
+  uint64_t generation = 0;
+  CHECK_STATUS(lifegame_world_generation(world, &generation), LIFEGAME_STATUS_OK);
+  CHECK(generation == 1);
+  uint8_t cell = 0;
+  CHECK_STATUS(lifegame_world_get_cell(world, 3, 2, &cell), LIFEGAME_STATUS_OK);
+  CHECK(cell == 1);
+  CHECK_STATUS(lifegame_world_get_cell(world, 2, 3, &cell), LIFEGAME_STATUS_OK);
+  CHECK(cell == 0);
+
+  uint8_t *cells = malloc(width * height);
+  CHECK_STATUS(lifegame_world_copy_cells(world, cells, width * height), LIFEGAME_STATUS_OK);
+  CHECK(cells[width * 2 + 3] == 1 && cells[width * 3 + 3] == 1 && cells[width * 4 + 3] == 1);
+  CHECK(cells[width * 3 + 2] == 0);
+  CHECK_STATUS(lifegame_world_copy_cells(world, cells, width * height - 1),
+               LIFEGAME_STATUS_BUFFER_TOO_SMALL);
+  free(cells);
+
+  /* errors */
+  CHECK_STATUS(lifegame_world_set_cell(world, 0, 0, 1), LIFEGAME_STATUS_OUT_OF_RANGE);
+  CHECK_STATUS(lifegame_world_get_cell(world, 7, 0, &cell), LIFEGAME_STATUS_OUT_OF_RANGE);
+  CHECK_STATUS(lifegame_world_step(NULL, 1), LIFEGAME_STATUS_NULL_POINTER);
+  CHECK_STATUS(lifegame_world_population(world, NULL), LIFEGAME_STATUS_NULL_POINTER);
+  LifegameWorld *invalid = NULL;
+  CHECK_STATUS(lifegame_world_create(7, 6, "B9/S", &invalid), LIFEGAME_STATUS_INVALID_ARGUMENT);
+  CHECK(invalid == NULL);
+  CHECK_STATUS(lifegame_world_create(0, 6, NULL, &invalid), LIFEGAME_STATUS_INVALID_ARGUMENT);
+  CHECK(invalid == NULL);
+  CHECK_STATUS(lifegame_world_create(7, 6, NULL, NULL), LIFEGAME_STATUS_NULL_POINTER);
+  CHECK(lifegame_status_message(LIFEGAME_STATUS_OUT_OF_RANGE) != NULL);
+
+  lifegame_world_destroy(world);
+  lifegame_world_destroy(NULL);
+
+  if (failures > 0) {
+    fprintf(stderr, "%d checks failed\n", failures);
+    return EXIT_FAILURE;
+  }
+  printf("all checks passed\n");
+  return EXIT_SUCCESS;
+}
@@ -2,59 +2,301 @@ use std::fmt::Debug;
 
 use anyhow::{Error, Result};
 
+pub mod library;
+pub mod margolus;
+pub mod neighbourhood;
+pub mod pattern;
+pub mod rule;
+pub mod snapshot;
+pub mod stochastic;
+pub mod table;
+
+pub use margolus::BlockRule;
+pub use neighbourhood::{Neighbourhood, Shape};
+pub use pattern::Pattern;
+pub use rule::Rule;
+pub use stochastic::Stochastic;
+pub use table::RuleTable;
+
+/// State of a cell. Generations rules have dying states from 2 up to the number of states.
 pub type Cell = u8;
 pub const CELL_DEAD: Cell = 0;
 pub const CELL_ALIVE: Cell = 1;
 
-#[derive(Debug)]
+/// Number of generations a cell has stayed in its present state
+pub type Age = u16;
+/// Age of dead cells which have never been alive
+pub const AGE_NEVER_ALIVE: Age = Age::MAX;
+
+#[derive(Clone, Debug)]
 pub struct World {
     nx: usize,
     ny: usize,
     cells: [Vec<Cell>; 2],
+    ages: Vec<Age>,
     present: usize,
     generation: usize,
+    rule: Rule,
 }
 
 impl World {
-    /// Create a new world
+    /// Create a new world, of 3 cells or more along both axes including the boundary
     pub fn new(nx: usize, ny: usize, cells: &[Cell]) -> Result<World> {
-        if cells.len() != nx * ny {
+        if nx < 3 || ny < 3 {
+            return Err(Error::msg("invalid world size."));
+        }
+        if nx.checked_mul(ny) != Some(cells.len()) {
             return Err(Error::msg("invalid cell size."));
         }
         let nsize = nx * ny;
+        let cells = process_boundary_cells(nx, ny, cells);
+        let ages = cells
+            .iter()
+            .map(|&cell| match cell {
+                CELL_DEAD => AGE_NEVER_ALIVE,
+                _ => 0,
+            })
+            .collect();
         Ok(World {
             nx,
             ny,
-            cells: [
-                process_boundary_cells(nx, ny, cells),
-                vec![CELL_DEAD; nsize],
-            ],
+            cells: [cells, vec![CELL_DEAD; nsize]],
+            ages,
             present: 0,
             generation: 0,
+            rule: Rule::default(),
         })
     }
 
+    /// Evolve the world by the rule instead of B3/S23
+    pub fn with_rule(mut self, rule: Rule) -> World {
+        self.rule = rule;
+        self
+    }
+
+    /// Rule the world evolves by
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
     pub fn next(&mut self) {
-        let next = (self.generation + 1) % 2;
+        if let Some(block) = self.rule.block().copied() {
+            self.step_blocks(&block, self.generation);
+            self.generation += 1;
+            return;
+        }
+        if let Some(table) = self.rule.shared_table() {
+            return self.next_by_table(&table);
+        }
+        match self.rule.neighbourhood() {
+            Neighbourhood::LargerThanLife {
+                range,
+                shape,
+                middle,
+            } => self.next_larger_than_life(range, shape, middle),
+            _ => self.next_by_neighbours(),
+        }
+        if let Some(stochastic) = self.rule.stochastic().copied() {
+            self.apply_stochastic(&stochastic);
+        }
+    }
+
+    /// Step the world by a rule of the nearest 8 cells or their subsets
+    fn next_by_neighbours(&mut self) {
+        let next = 1 - self.present;
+        let totalistic = self.rule.is_totalistic();
+        // ages of stochastic rules are counted after the noise
+        let count_ages = self.rule.stochastic().is_none();
         for iy in 1..(self.ny - 1) {
             for ix in 1..(self.nx - 1) {
                 let present_cell = self.get_cell(self.present, ix, iy);
-                let num_alive_neighbours = self.count_alive_neighbours(self.present, ix, iy);
-                let next_cell = (num_alive_neighbours == 3
-                    || (num_alive_neighbours == 2 && present_cell == CELL_ALIVE))
-                    as u8;
+                let next_cell = match totalistic {
+                    true => {
+                        let num_alive_neighbours =
+                            self.count_alive_neighbours(self.present, ix, iy);
+                        self.rule.next(present_cell, num_alive_neighbours)
+                    }
+                    false => {
+                        let configuration = self.neighbour_configuration(self.present, ix, iy);
+                        self.rule.next_by_configuration(present_cell, configuration)
+                    }
+                };
                 self.update_cell(next, ix, iy, next_cell);
+                if count_ages {
+                    self.update_age(ix, iy, present_cell == next_cell);
+                }
             }
         }
         self.generation += 1;
         self.present = next;
     }
 
+    /// World size along with x-axis, including the boundary
+    pub fn nx(&self) -> usize {
+        self.nx
+    }
+
+    /// World size along with y-axis, including the boundary
+    pub fn ny(&self) -> usize {
+        self.ny
+    }
+
+    /// Create a world of a new size keeping the cells, ages and generation.
+    /// Cells keep their indices, and cells outside the new world are dropped.
+    pub fn resized(&self, nx: usize, ny: usize) -> Result<World> {
+        let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny])?;
+        for iy in 1..(self.ny - 1).min(ny - 1) {
+            for ix in 1..(self.nx - 1).min(nx - 1) {
+                world.update_cell(0, ix, iy, self.get_present_cell(ix, iy));
+                world.ages[nx * iy + ix] = self.get_present_age(ix, iy);
+            }
+        }
+        world.generation = self.generation;
+        world.rule = self.rule.clone();
+        Ok(world)
+    }
+
+    /// Smallest rectangle containing all alive cells as `(ix, iy, width, height)`,
+    /// or `None` if there is no alive cell.
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        self.bounding_box_in(0, 0, self.nx, self.ny)
+    }
+
+    /// Smallest rectangle containing all alive cells in the rectangle `(ix, iy, width, height)`.
+    /// The rectangle is clipped to the world.
+    pub fn bounding_box_in(
+        &self,
+        ix: usize,
+        iy: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize, usize, usize)> {
+        let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+        for (x, y) in self.alive_cells_in(ix, iy, width, height) {
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x);
+            y1 = y1.max(y);
+        }
+        (x0 <= x1).then(|| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+    }
+
+    /// Mean position of all alive cells, or `None` if there is no alive cell.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        self.centroid_in(0, 0, self.nx, self.ny)
+    }
+
+    /// Mean position of alive cells in the rectangle `(ix, iy, width, height)`.
+    /// The rectangle is clipped to the world.
+    pub fn centroid_in(
+        &self,
+        ix: usize,
+        iy: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<(f64, f64)> {
+        let (mut sx, mut sy, mut count) = (0, 0, 0);
+        for (x, y) in self.alive_cells_in(ix, iy, width, height) {
+            sx += x;
+            sy += y;
+            count += 1;
+        }
+        (count > 0).then(|| (sx as f64 / count as f64, sy as f64 / count as f64))
+    }
+
+    /// Number of alive cells
+    pub fn population(&self) -> usize {
+        self.alive_cells().count()
+    }
+
+    /// Indices of alive cells row by row
+    pub fn alive_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.alive_cells_in(0, 0, self.nx, self.ny)
+    }
+
+    /// Indices of alive cells in the rectangle row by row, clipped to the world
+    pub fn alive_cells_in(
+        &self,
+        ix: usize,
+        iy: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let xs = ix.min(self.nx)..ix.saturating_add(width).min(self.nx);
+        let ys = iy.min(self.ny)..iy.saturating_add(height).min(self.ny);
+        ys.flat_map(move |y| xs.clone().map(move |x| (x, y)))
+            .filter(|&(x, y)| self.get_present_cell(x, y) == CELL_ALIVE)
+    }
+
+    /// Number of generations since the world was created
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Check if the cell is inside the world, excluding the always-dead boundary
+    #[inline]
+    pub fn is_inner(&self, ix: usize, iy: usize) -> bool {
+        (1..self.nx - 1).contains(&ix) && (1..self.ny - 1).contains(&iy)
+    }
+
+    /// Set a cell of the present generation. Cells on the boundary are left dead.
+    pub fn set_present_cell(&mut self, ix: usize, iy: usize, cell: Cell) {
+        if !self.is_inner(ix, iy) {
+            return;
+        }
+        if self.get_cell(self.present, ix, iy) != cell {
+            self.update_cell(self.present, ix, iy, cell);
+            self.update_age(ix, iy, false);
+        }
+    }
+
+    /// Copy all cells of the pattern with its top-left corner at (ix, iy).
+    /// Cells outside the world are dropped.
+    pub fn paste(&mut self, pattern: &Pattern, ix: usize, iy: usize) {
+        for py in 0..pattern.height {
+            for px in 0..pattern.width {
+                self.set_present_cell(ix + px, iy + py, pattern.get_cell(px, py));
+            }
+        }
+    }
+
+    /// Copy cells of the present generation in a rectangle into a pattern.
+    /// The rectangle is clipped to the world.
+    pub fn extract(&self, ix: usize, iy: usize, width: usize, height: usize) -> Pattern {
+        let (ix, iy) = (ix.min(self.nx), iy.min(self.ny));
+        let width = width.min(self.nx - ix);
+        let height = height.min(self.ny - iy);
+        let mut cells = Vec::with_capacity(width * height);
+        for y in iy..iy + height {
+            for x in ix..ix + width {
+                cells.push(self.get_present_cell(x, y));
+            }
+        }
+        Pattern::new(width, height, &cells).expect("invalid pattern size")
+    }
+
+    /// Cells of the present generation row by row, including the boundary
+    pub fn present_cells(&self) -> &[Cell] {
+        &self.cells[self.present]
+    }
+
     #[inline]
     pub fn get_present_cell(&self, ix: usize, iy: usize) -> Cell {
         self.get_cell(self.present, ix, iy)
     }
 
+    /// Get the number of generations the cell has stayed alive, or stayed dead since it died.
+    ///
+    /// Dead cells which have never been alive are [`AGE_NEVER_ALIVE`].
+    #[inline]
+    pub fn get_present_age(&self, ix: usize, iy: usize) -> Age {
+        self.ages[self.nx * iy + ix]
+    }
+
     #[inline]
     fn get_cell(&self, index: usize, ix: usize, iy: usize) -> Cell {
         self.cells[index][self.nx * iy + ix]
@@ -65,16 +307,44 @@ impl World {
         self.cells[index][self.nx * iy + ix] = cell;
     }
 
+    #[inline]
+    fn update_age(&mut self, ix: usize, iy: usize, unchanged: bool) {
+        let age = &mut self.ages[self.nx * iy + ix];
+        *age = match unchanged {
+            true => age.saturating_add(1),
+            false => 0,
+        };
+    }
+
     #[inline]
     fn count_alive_neighbours(&self, index: usize, ix: usize, iy: usize) -> u8 {
-        self.get_cell(index, ix - 1, iy - 1) // NW
-            + self.get_cell(index, ix, iy - 1)    // N
-            + self.get_cell(index, ix + 1, iy - 1) // NE
-            + self.get_cell(index, ix - 1, iy)    // W
-            + self.get_cell(index, ix + 1, iy)    // E
-            + self.get_cell(index, ix - 1, iy + 1) // SW
-            + self.get_cell(index, ix, iy + 1)     // S
-            + self.get_cell(index, ix + 1, iy + 1) // SE
+        self.is_alive(index, ix - 1, iy - 1) // NW
+            + self.is_alive(index, ix, iy - 1)    // N
+            + self.is_alive(index, ix + 1, iy - 1) // NE
+            + self.is_alive(index, ix - 1, iy)    // W
+            + self.is_alive(index, ix + 1, iy)    // E
+            + self.is_alive(index, ix - 1, iy + 1) // SW
+            + self.is_alive(index, ix, iy + 1)     // S
+            + self.is_alive(index, ix + 1, iy + 1) // SE
+    }
+
+    /// Alive neighbours as bits of NW, N, NE, W, E, SW, S and SE from the lowest bit
+    #[inline]
+    fn neighbour_configuration(&self, index: usize, ix: usize, iy: usize) -> u8 {
+        self.is_alive(index, ix - 1, iy - 1)
+            | self.is_alive(index, ix, iy - 1) << 1
+            | self.is_alive(index, ix + 1, iy - 1) << 2
+            | self.is_alive(index, ix - 1, iy) << 3
+            | self.is_alive(index, ix + 1, iy) << 4
+            | self.is_alive(index, ix - 1, iy + 1) << 5
+            | self.is_alive(index, ix, iy + 1) << 6
+            | self.is_alive(index, ix + 1, iy + 1) << 7
+    }
+
+    /// 1 for an alive cell, and 0 for dead or dying cells
+    #[inline]
+    fn is_alive(&self, index: usize, ix: usize, iy: usize) -> u8 {
+        (self.get_cell(index, ix, iy) == CELL_ALIVE) as u8
     }
 }
 
@@ -88,6 +358,19 @@ fn process_boundary_cells(nx: usize, ny: usize, cells: &[Cell]) -> Vec<Cell> {
     processed_cells
 }
 
+/// A pseudo-random soup of about a third alive cells for tests
+#[cfg(test)]
+pub(crate) fn soup(width: usize, height: usize, seed: u32) -> Pattern {
+    let mut seed = seed;
+    let cells = (0..width * height)
+        .map(|_| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as u8 % 3 / 2
+        })
+        .collect::<Vec<_>>();
+    Pattern::new(width, height, &cells).expect("cells of the size")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +393,11 @@ mod tests {
         let space = World::new(2, 2, &[CELL_ALIVE; 5]);
         assert!(space.is_err());
 
+        // too small for the boundary
+        assert!(World::new(0, 6, &[]).is_err());
+        assert!(World::new(3, 2, &[CELL_DEAD; 6]).is_err());
+        assert!(World::new(3, 3, &[CELL_DEAD; 9]).is_ok());
+
         let space = World::new(
             4,
             4,
@@ -276,4 +564,250 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn paste_and_extract() {
+        let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        let mut space = World::new(6, 6, &[CELL_DEAD; 36]).unwrap();
+        space.paste(&glider, 1, 1);
+        assert_eq!(space.extract(1, 1, 3, 3), glider);
+        assert_eq!(space.get_present_age(3, 3), 0);
+        assert_eq!(space.population(), 5);
+        assert_eq!(
+            space.alive_cells_in(1, 2, 2, 5).collect::<Vec<_>>(),
+            [(1, 3), (2, 3)]
+        );
+
+        // clipped at the boundary
+        space.paste(&glider, 3, 3);
+        assert_eq!(
+            space.extract(3, 3, 10, 10).cells,
+            [
+                [CELL_DEAD, CELL_ALIVE, CELL_DEAD],
+                [CELL_DEAD, CELL_DEAD, CELL_DEAD],
+                [CELL_DEAD, CELL_DEAD, CELL_DEAD],
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn resize() {
+        let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        let mut space = World::new(6, 6, &[CELL_DEAD; 36]).unwrap();
+        space.paste(&glider, 1, 1);
+        space.next();
+
+        let larger = space.resized(10, 8).unwrap();
+        assert_eq!((larger.nx(), larger.ny()), (10, 8));
+        assert_eq!(larger.generation(), 1);
+        assert_eq!(larger.extract(0, 0, 6, 6), space.extract(0, 0, 6, 6));
+        assert_eq!(larger.get_present_age(2, 2), space.get_present_age(2, 2));
+        assert_eq!(larger.get_present_age(6, 6), AGE_NEVER_ALIVE);
+
+        // the resized world keeps evolving after an odd generation.
+        let mut larger = larger;
+        let mut space = space.resized(10, 8).unwrap();
+        larger.next();
+        space.next();
+        assert_eq!(larger.extract(0, 0, 10, 8), space.extract(0, 0, 10, 8));
+        assert_eq!(larger.population(), 5);
+        assert_eq!(
+            larger.present_cells(),
+            &space.extract(0, 0, 10, 8).cells[..]
+        );
+
+        // cells on the new boundary are dropped.
+        let smaller = space.resized(4, 4).unwrap();
+        assert_eq!(smaller.extract(0, 0, 4, 4).cells, {
+            let mut cells = space.extract(0, 0, 4, 4).cells;
+            for i in [3, 7, 11, 12, 13, 14, 15] {
+                cells[i] = CELL_DEAD;
+            }
+            cells
+        });
+        assert!(space.resized(2, 10).is_err());
+    }
+
+    #[test]
+    fn bounding_box() {
+        let mut space = World::new(8, 8, &[CELL_DEAD; 64]).unwrap();
+        assert_eq!(space.bounding_box(), None);
+        space.set_present_cell(2, 5, CELL_ALIVE);
+        assert_eq!(space.bounding_box(), Some((2, 5, 1, 1)));
+        space.set_present_cell(4, 3, CELL_ALIVE);
+        space.set_present_cell(3, 6, CELL_ALIVE);
+        assert_eq!(space.bounding_box(), Some((2, 3, 3, 4)));
+        assert_eq!(space.bounding_box_in(3, 0, 10, 10), Some((3, 3, 2, 4)));
+        assert_eq!(space.bounding_box_in(5, 0, 10, 10), None);
+    }
+
+    #[test]
+    fn centroid() {
+        let mut space = World::new(8, 8, &[CELL_DEAD; 64]).unwrap();
+        assert_eq!(space.centroid(), None);
+        space.set_present_cell(2, 5, CELL_ALIVE);
+        space.set_present_cell(4, 3, CELL_ALIVE);
+        assert_eq!(space.centroid(), Some((3.0, 4.0)));
+        space.set_present_cell(6, 6, CELL_ALIVE);
+        assert_eq!(space.centroid_in(0, 0, 5, 5), Some((4.0, 3.0)));
+        assert_eq!(space.centroid_in(0, 0, 2, 2), None);
+    }
+
+    #[test]
+    fn rule() {
+        // B36/S23 (HighLife) gives birth with 6 neighbours.
+        let cells = expand_boundary(
+            3,
+            3,
+            &[
+                CELL_ALIVE, CELL_ALIVE, CELL_ALIVE, CELL_ALIVE, CELL_DEAD, CELL_ALIVE, CELL_ALIVE,
+                CELL_DEAD, CELL_DEAD,
+            ],
+        );
+        let mut life = World::new(5, 5, &cells).unwrap();
+        let mut highlife = World::new(5, 5, &cells)
+            .unwrap()
+            .with_rule("B36/S23".parse().unwrap());
+        life.next();
+        highlife.next();
+        assert_eq!(life.get_present_cell(2, 2), CELL_DEAD);
+        assert_eq!(highlife.get_present_cell(2, 2), CELL_ALIVE);
+        assert_eq!(highlife.resized(6, 6).unwrap().rule(), highlife.rule());
+    }
+
+    #[test]
+    fn cell_age() {
+        // blinker
+        let mut space = World::new(
+            5,
+            5,
+            &expand_boundary(
+                3,
+                3,
+                &[
+                    [CELL_DEAD, CELL_ALIVE, CELL_DEAD],
+                    [CELL_DEAD, CELL_ALIVE, CELL_DEAD],
+                    [CELL_DEAD, CELL_ALIVE, CELL_DEAD],
+                ]
+                .concat(),
+            ),
+        )
+        .unwrap();
+        assert_eq!(space.get_present_age(2, 2), 0);
+        assert_eq!(space.get_present_age(2, 1), 0);
+        assert_eq!(space.get_present_age(1, 2), AGE_NEVER_ALIVE);
+        space.next();
+        assert_eq!(space.get_present_age(2, 2), 1);
+        assert_eq!(space.get_present_age(2, 1), 0);
+        assert_eq!(space.get_present_age(1, 2), 0);
+        assert_eq!(space.get_present_age(1, 1), AGE_NEVER_ALIVE);
+        space.next();
+        space.next();
+        assert_eq!(space.get_present_age(2, 2), 3);
+        assert_eq!(space.get_present_age(2, 1), 0);
+        assert_eq!(space.get_present_age(1, 2), 0);
+        assert_eq!(space.get_present_cell(1, 2), CELL_ALIVE);
+        space.next();
+        assert_eq!(space.get_present_cell(1, 2), CELL_DEAD);
+        assert_eq!(space.get_present_age(1, 2), 0);
+    }
+
+    #[test]
+    fn generations_rule() -> Result<()> {
+        let brians_brain: Rule = "/2/3".parse()?;
+        let mut world = World::new(6, 5, &[CELL_DEAD; 30])?.with_rule(brians_brain.clone());
+        world.set_present_cell(2, 2, CELL_ALIVE);
+        world.set_present_cell(3, 2, CELL_ALIVE);
+        world.next();
+        assert_eq!(world.extract(1, 1, 4, 3).cells, {
+            let (o, b, d) = (CELL_ALIVE, CELL_DEAD, 2);
+            [[b, o, o, b], [b, d, d, b], [b, o, o, b]].concat()
+        });
+        assert_eq!(world.population(), 4);
+
+        // dying cells are not counted as alive neighbours
+        let mut world = World::new(6, 5, &[CELL_DEAD; 30])?.with_rule(brians_brain);
+        world.set_present_cell(2, 2, 2);
+        world.set_present_cell(3, 2, 2);
+        world.next();
+        assert_eq!(world.population(), 0);
+        assert!(world.present_cells().iter().all(|&c| c == CELL_DEAD));
+        Ok(())
+    }
+
+    #[test]
+    fn hensel_rule() -> Result<()> {
+        let soup = soup(24, 16, 12345);
+        let world = |pattern: &Pattern, rule: Rule| -> Result<World> {
+            let mut world = World::new(34, 34, &[CELL_DEAD; 34 * 34])?.with_rule(rule);
+            world.paste(pattern, 5, 5);
+            Ok(world)
+        };
+
+        // Life in Hensel notation with all letters
+        let mut life = world(&soup, Rule::conway())?;
+        let mut hensel = world(&soup, "B3aceijknqry/S2aceikn3".parse()?)?;
+        for _ in 0..10 {
+            life.next();
+            hensel.next();
+        }
+        assert_eq!(life.present_cells(), hensel.present_cells());
+
+        // the center of a blinker has the neighbours N and S of 2i, and does not survive
+        let mut blinker = World::new(7, 7, &[CELL_DEAD; 49])?.with_rule("B3/S2-i3".parse()?);
+        for iy in 2..5 {
+            blinker.set_present_cell(3, iy, CELL_ALIVE);
+        }
+        blinker.next();
+        assert_eq!(blinker.alive_cells().collect::<Vec<_>>(), [(2, 3), (4, 3)]);
+
+        // stepping is isotropic
+        let tlife: Rule = "B3/S2-i34q".parse()?;
+        let mut world = world(&soup, tlife.clone())?;
+        let mut rotated = World::new(34, 34, &[CELL_DEAD; 34 * 34])?.with_rule(tlife);
+        rotated.paste(&soup.rotated_clockwise(), 13, 5);
+        for _ in 0..10 {
+            world.next();
+            rotated.next();
+        }
+        assert_eq!(
+            world.extract(1, 1, 32, 32).rotated_clockwise().cells,
+            rotated.extract(1, 1, 32, 32).cells
+        );
+        assert_ne!(world.present_cells(), life.present_cells());
+        Ok(())
+    }
+
+    #[test]
+    fn neighbourhood_rule() -> Result<()> {
+        // a plus of 5 cells in B1/S1V, where the center has 4 von Neumann neighbours
+        // and the corners have 2
+        let mut world = World::new(9, 9, &[CELL_DEAD; 81])?.with_rule("B1/S1V".parse()?);
+        for (ix, iy) in [(4, 3), (3, 4), (4, 4), (5, 4), (4, 5)] {
+            world.set_present_cell(ix, iy, CELL_ALIVE);
+        }
+        world.next();
+        assert_eq!(
+            world.alive_cells().collect::<Vec<_>>(),
+            [
+                (4, 2),
+                (4, 3),
+                (2, 4),
+                (3, 4),
+                (5, 4),
+                (6, 4),
+                (4, 5),
+                (4, 6)
+            ]
+        );
+
+        // a pair of NE and SW is not neighbours in the hexagonal neighbourhood
+        let mut world = World::new(6, 6, &[CELL_DEAD; 36])?.with_rule("B2/S1H".parse()?);
+        world.set_present_cell(3, 2, CELL_ALIVE);
+        world.set_present_cell(2, 3, CELL_ALIVE);
+        world.next();
+        assert_eq!(world.alive_cells().collect::<Vec<_>>(), [(2, 2), (3, 3)]);
+        Ok(())
+    }
 }
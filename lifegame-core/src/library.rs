@@ -0,0 +1,324 @@
+use crate::Pattern;
+
+/// Category of library patterns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    Puffer,
+    Methuselah,
+}
+
+impl Category {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::StillLife => "still life",
+            Category::Oscillator => "oscillator",
+            Category::Spaceship => "spaceship",
+            Category::Gun => "gun",
+            Category::Puffer => "puffer",
+            Category::Methuselah => "methuselah",
+        }
+    }
+}
+
+/// Well-known pattern embedded in the library.
+#[derive(Debug)]
+pub struct LibraryEntry {
+    pub name: &'static str,
+    pub category: Category,
+    pub description: &'static str,
+    rle: &'static str,
+}
+
+impl LibraryEntry {
+    /// Cells of the pattern
+    pub fn pattern(&self) -> Pattern {
+        let mut pattern = Pattern::from_rle(self.rle).expect("invalid library pattern");
+        pattern.name = Some(self.name.to_string());
+        pattern
+    }
+
+    /// Check if the name, category or description contains the query, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [self.name, self.category.name(), self.description]
+            .iter()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+/// Built-in patterns of B3/S23.
+pub const LIBRARY: &[LibraryEntry] = &[
+    LibraryEntry {
+        name: "Block",
+        category: Category::StillLife,
+        description: "The most common still life.",
+        rle: "x = 2, y = 2\n2o$2o!",
+    },
+    LibraryEntry {
+        name: "Beehive",
+        category: Category::StillLife,
+        description: "The second most common still life.",
+        rle: "x = 4, y = 3\nb2o$o2bo$b2o!",
+    },
+    LibraryEntry {
+        name: "Loaf",
+        category: Category::StillLife,
+        description: "7-cell still life.",
+        rle: "x = 4, y = 4\nb2o$o2bo$bobo$2bo!",
+    },
+    LibraryEntry {
+        name: "Boat",
+        category: Category::StillLife,
+        description: "The only 5-cell still life.",
+        rle: "x = 3, y = 3\n2o$obo$bo!",
+    },
+    LibraryEntry {
+        name: "Blinker",
+        category: Category::Oscillator,
+        description: "The smallest and most common oscillator, period 2.",
+        rle: "x = 3, y = 1\n3o!",
+    },
+    LibraryEntry {
+        name: "Toad",
+        category: Category::Oscillator,
+        description: "Period 2 oscillator of two offset rows.",
+        rle: "x = 4, y = 2\nb3o$3o!",
+    },
+    LibraryEntry {
+        name: "Beacon",
+        category: Category::Oscillator,
+        description: "Period 2 oscillator of two diagonal blocks.",
+        rle: "x = 4, y = 4\n2o$o$3bo$2b2o!",
+    },
+    LibraryEntry {
+        name: "Pulsar",
+        category: Category::Oscillator,
+        description: "The most common period 3 oscillator.",
+        rle: "x = 13, y = 13\n\
+              2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$\n\
+              o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    },
+    LibraryEntry {
+        name: "Pentadecathlon",
+        category: Category::Oscillator,
+        description: "Period 15 oscillator evolving from a row of ten cells.",
+        rle: "x = 10, y = 3\n2bo4bo$2ob4ob2o$2bo4bo!",
+    },
+    LibraryEntry {
+        name: "Glider",
+        category: Category::Spaceship,
+        description: "The smallest spaceship, moving diagonally at c/4.",
+        rle: "x = 3, y = 3\nbo$2bo$3o!",
+    },
+    LibraryEntry {
+        name: "Lightweight spaceship",
+        category: Category::Spaceship,
+        description: "LWSS, the smallest orthogonal spaceship moving at c/2.",
+        rle: "x = 5, y = 4\nbo2bo$o$o3bo$4o!",
+    },
+    LibraryEntry {
+        name: "Middleweight spaceship",
+        category: Category::Spaceship,
+        description: "MWSS, orthogonal spaceship moving at c/2.",
+        rle: "x = 6, y = 5\n3bo$bo3bo$o$o4bo$5o!",
+    },
+    LibraryEntry {
+        name: "Heavyweight spaceship",
+        category: Category::Spaceship,
+        description: "HWSS, orthogonal spaceship moving at c/2.",
+        rle: "x = 7, y = 5\n3b2o$bo4bo$o$o5bo$6o!",
+    },
+    LibraryEntry {
+        name: "Gosper glider gun",
+        category: Category::Gun,
+        description: "The first known gun, emitting a glider every 30 generations.",
+        rle: "x = 36, y = 9\n\
+              24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b\n\
+              obo$10bo5bo7bo$11bo3bo$12b2o!",
+    },
+    LibraryEntry {
+        name: "Simkin glider gun",
+        category: Category::Gun,
+        description: "Gun emitting a glider every 120 generations.",
+        rle: "x = 33, y = 21\n\
+              2o5b2o$2o5b2o2$4b2o$4b2o5$22b2ob2o$21bo5bo$21bo6bo2b2o$21b3o3bo3b2o$26b\n\
+              o4$20b2o$20bo$21b3o$23bo!",
+    },
+    LibraryEntry {
+        name: "Infinite growth 5x5",
+        category: Category::Puffer,
+        description: "The smallest infinite growth pattern, becoming a block-laying switch engine.",
+        rle: "x = 5, y = 5\n3obo$o$3b2o$b2obo$obobo!",
+    },
+    LibraryEntry {
+        name: "Infinite growth 1-row",
+        category: Category::Puffer,
+        description: "Single row pattern becoming two block-laying switch engines.",
+        rle: "x = 39, y = 1\n8ob5o3b3o6b7ob5o!",
+    },
+    LibraryEntry {
+        name: "R-pentomino",
+        category: Category::Methuselah,
+        description: "5 cells stabilizing after 1103 generations.",
+        rle: "x = 3, y = 3\nb2o$2o$bo!",
+    },
+    LibraryEntry {
+        name: "Diehard",
+        category: Category::Methuselah,
+        description: "Vanishes completely after 130 generations.",
+        rle: "x = 8, y = 3\n6bo$2o$bo3b3o!",
+    },
+    LibraryEntry {
+        name: "Acorn",
+        category: Category::Methuselah,
+        description: "7 cells stabilizing after 5206 generations.",
+        rle: "x = 7, y = 3\nbo$3bo$2o2b3o!",
+    },
+];
+
+/// Library patterns matching the query
+pub fn search(query: &str) -> impl Iterator<Item = &'static LibraryEntry> + '_ {
+    LIBRARY.iter().filter(move |entry| entry.matches(query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{World, CELL_ALIVE, CELL_DEAD};
+
+    const MARGIN: usize = 100;
+
+    fn find(name: &str) -> &'static LibraryEntry {
+        LIBRARY.iter().find(|entry| entry.name == name).unwrap()
+    }
+
+    fn make_world(entry: &LibraryEntry, margin: usize) -> World {
+        let pattern = entry.pattern();
+        let (nx, ny) = (pattern.width + 2 * margin, pattern.height + 2 * margin);
+        let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny]).unwrap();
+        world.paste(&pattern, margin, margin);
+        world
+    }
+
+    /// Alive cells relative to the top-left alive cell, and the offset
+    fn shape(world: &World) -> (Vec<(usize, usize)>, (usize, usize)) {
+        let mut cells = Vec::new();
+        for iy in 0..world.ny() {
+            for ix in 0..world.nx() {
+                if world.get_present_cell(ix, iy) == CELL_ALIVE {
+                    cells.push((ix, iy));
+                }
+            }
+        }
+        let x0 = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let y0 = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        let cells = cells.iter().map(|(x, y)| (x - x0, y - y0)).collect();
+        (cells, (x0, y0))
+    }
+
+    fn population(world: &World) -> usize {
+        shape(world).0.len()
+    }
+
+    #[test]
+    fn test_library_patterns_are_valid() {
+        for entry in LIBRARY {
+            let pattern = entry.pattern();
+            assert!(pattern.cells.contains(&CELL_ALIVE), "{}", entry.name);
+            assert_eq!(pattern.name.as_deref(), Some(entry.name));
+        }
+    }
+
+    #[test]
+    fn test_periodic_patterns() {
+        for (name, period, shift) in [
+            ("Block", 1, (0, 0)),
+            ("Beehive", 1, (0, 0)),
+            ("Loaf", 1, (0, 0)),
+            ("Boat", 1, (0, 0)),
+            ("Blinker", 2, (0, 0)),
+            ("Toad", 2, (0, 0)),
+            ("Beacon", 2, (0, 0)),
+            ("Pulsar", 3, (0, 0)),
+            ("Pentadecathlon", 15, (0, 0)),
+            ("Glider", 4, (1, 1)),
+            ("Lightweight spaceship", 4, (2, 0)),
+            ("Middleweight spaceship", 4, (2, 0)),
+            ("Heavyweight spaceship", 4, (2, 0)),
+        ] {
+            let mut world = make_world(find(name), 10);
+            // oscillators and spaceships reappear after the period, not earlier.
+            let (initial, (x0, y0)) = shape(&world);
+            for _ in 0..period - 1 {
+                world.next();
+                assert_ne!(shape(&world).0, initial, "{}", name);
+            }
+            world.next();
+            let (cells, (x, y)) = shape(&world);
+            assert_eq!(cells, initial, "{}", name);
+            assert_eq!((x.abs_diff(x0), y.abs_diff(y0)), shift, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_guns() {
+        for (name, period) in [("Gosper glider gun", 30), ("Simkin glider gun", 120)] {
+            let mut world = make_world(find(name), MARGIN);
+            // wait for the first glider, then every period adds one glider.
+            for _ in 0..period {
+                world.next();
+            }
+            let base = population(&world);
+            for n in 1..=2 {
+                for _ in 0..period {
+                    world.next();
+                }
+                assert_eq!(population(&world), base + 5 * n, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_puffers() {
+        // the switch engine keeps leaving blocks behind it.
+        let mut world = make_world(find("Infinite growth 5x5"), 80);
+        for _ in 0..300 {
+            world.next();
+        }
+        let early = population(&world);
+        for _ in 0..600 {
+            world.next();
+        }
+        assert!(population(&world) > early + early / 4);
+    }
+
+    #[test]
+    fn test_methuselahs() {
+        let mut world = make_world(find("Diehard"), MARGIN);
+        for _ in 0..129 {
+            world.next();
+        }
+        assert!(population(&world) > 0);
+        world.next();
+        assert_eq!(population(&world), 0);
+
+        // R-pentomino is still active long after it started.
+        let mut world = make_world(find("R-pentomino"), 60);
+        for _ in 0..200 {
+            world.next();
+        }
+        assert!(population(&world) > 100);
+    }
+
+    #[test]
+    fn test_search() {
+        assert_eq!(search("GLIDER GUN").count(), 2);
+        assert!(search("spaceship").any(|entry| entry.name == "Glider"));
+        assert_eq!(search("").count(), LIBRARY.len());
+        assert_eq!(search("no such pattern").count(), 0);
+    }
+}
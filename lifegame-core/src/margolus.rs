@@ -0,0 +1,245 @@
+//! Block cellular automata of the Margolus neighbourhood.
+//!
+//! The world is partitioned into 2x2 blocks, whose offsets alternate between even and odd
+//! generations, and every block is replaced by the rule over its 16 configurations.
+//! A rule which is a permutation of the configurations is reversible, and steps backward
+//! exactly by its inverse.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+
+use crate::{Rule, World, CELL_ALIVE, CELL_DEAD};
+
+/// Rule of 2x2 blocks, mapping the configuration of a block to the next one.
+///
+/// The bits of a configuration are 1 for the top-left cell, 2 for the top-right, 4 for the
+/// bottom-left and 8 for the bottom-right, and rules are written in the MCell notation like
+/// `MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlockRule([u8; 16]);
+
+impl BlockRule {
+    /// The billiard ball model, where balls move diagonally and bounce off each other
+    pub const BILLIARD_BALL_MODEL: BlockRule =
+        BlockRule([0, 8, 4, 3, 2, 5, 9, 7, 1, 6, 10, 11, 12, 13, 14, 15]);
+    /// Critters, complementing blocks of other than 2 alive cells and rotating blocks of 3
+    pub const CRITTERS: BlockRule =
+        BlockRule([15, 14, 13, 3, 11, 5, 6, 1, 7, 9, 10, 2, 12, 4, 8, 0]);
+
+    /// Create a block rule of the next configurations of all 16 configurations
+    pub fn new(table: [u8; 16]) -> Result<BlockRule> {
+        match table.iter().find(|&&c| c >= 16) {
+            Some(c) => Err(Error::msg(format!("invalid block configuration {}.", c))),
+            None => Ok(BlockRule(table)),
+        }
+    }
+
+    /// The next configuration of a block
+    #[inline]
+    pub fn next(&self, configuration: u8) -> u8 {
+        self.0[configuration as usize & 0xf]
+    }
+
+    /// Whether every configuration has a different next configuration
+    pub fn is_reversible(&self) -> bool {
+        self.inverse().is_some()
+    }
+
+    /// The rule stepping backward, or `None` if the rule is not reversible
+    pub fn inverse(&self) -> Option<BlockRule> {
+        let mut inverse = [None; 16];
+        for (configuration, &next) in self.0.iter().enumerate() {
+            if inverse[next as usize]
+                .replace(configuration as u8)
+                .is_some()
+            {
+                return None;
+            }
+        }
+        Some(BlockRule(inverse.map(|c| c.expect("a permutation"))))
+    }
+}
+
+impl FromStr for BlockRule {
+    type Err = Error;
+
+    /// Parse a rule written like `MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15`
+    fn from_str(text: &str) -> Result<BlockRule> {
+        let invalid = || Error::msg(format!("invalid block rule '{}'.", text));
+        let configurations = text
+            .trim()
+            .to_uppercase()
+            .strip_prefix("MS,D")
+            .ok_or_else(invalid)?
+            .split(';')
+            .map(|c| c.trim().parse::<u8>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>>>()?;
+        BlockRule::new(configurations.try_into().map_err(|_| invalid())?)
+    }
+}
+
+impl fmt::Display for BlockRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MS,D")?;
+        for (i, configuration) in self.0.iter().enumerate() {
+            match i {
+                0 => write!(f, "{}", configuration)?,
+                _ => write!(f, ";{}", configuration)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl World {
+    /// Step the world back to the previous generation by a reversible block rule.
+    ///
+    /// Ages are counted as if stepping forward.
+    pub fn previous(&mut self) -> Result<()> {
+        let Some(block) = self.rule.block() else {
+            return Err(Error::msg("only block rules step backward."));
+        };
+        let inverse = block
+            .inverse()
+            .ok_or(Error::msg("the block rule is not reversible."))?;
+        if self.generation == 0 {
+            return Err(Error::msg("no generation before 0."));
+        }
+        self.step_blocks(&inverse, self.generation - 1);
+        self.generation -= 1;
+        Ok(())
+    }
+
+    /// Replace the blocks of the generation by a block rule, keeping the generation.
+    ///
+    /// Blocks start at the first inner cell in even generations and one cell right and down in
+    /// odd generations, and cells out of the whole blocks in the inner cells are unchanged.
+    pub(crate) fn step_blocks(&mut self, block: &BlockRule, generation: usize) {
+        let next = 1 - self.present;
+        let [first, second] = &mut self.cells;
+        let (present_cells, next_cells) = match self.present {
+            0 => (&*first, second),
+            _ => (&*second, first),
+        };
+        next_cells.copy_from_slice(present_cells);
+
+        let offset = 1 + generation % 2;
+        let nx = self.nx;
+        for iy in (offset..self.ny - 2).step_by(2) {
+            for ix in (offset..self.nx - 2).step_by(2) {
+                let indices = [
+                    nx * iy + ix,
+                    nx * iy + ix + 1,
+                    nx * (iy + 1) + ix,
+                    nx * (iy + 1) + ix + 1,
+                ];
+                let configuration = indices
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &i)| present_cells[i] == CELL_ALIVE)
+                    .fold(0, |c, (bit, _)| c | 1 << bit);
+                let configuration = block.next(configuration);
+                for (bit, &i) in indices.iter().enumerate() {
+                    next_cells[i] = match configuration >> bit & 1 {
+                        1 => CELL_ALIVE,
+                        _ => CELL_DEAD,
+                    };
+                }
+            }
+        }
+
+        for iy in 1..(self.ny - 1) {
+            for ix in 1..(self.nx - 1) {
+                let unchanged = self.get_cell(self.present, ix, iy) == self.get_cell(next, ix, iy);
+                self.update_age(ix, iy, unchanged);
+            }
+        }
+        self.present = next;
+    }
+}
+
+impl Rule {
+    /// Create a rule of 2x2 blocks of the Margolus neighbourhood
+    pub fn margolus(block: BlockRule) -> Rule {
+        Rule::with_block(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soup;
+
+    #[test]
+    fn test_parse_block_rule() -> Result<()> {
+        let text = "MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15";
+        assert_eq!(text.parse::<BlockRule>()?, BlockRule::BILLIARD_BALL_MODEL);
+        assert_eq!(BlockRule::BILLIARD_BALL_MODEL.to_string(), text);
+        let rule: Rule = "ms,d15;14;13;3;11;5;6;1;7;9;10;2;12;4;8;0".parse()?;
+        assert_eq!(rule.block(), Some(&BlockRule::CRITTERS));
+        assert_eq!(rule.to_string(), BlockRule::CRITTERS.to_string());
+
+        assert!("MS,D0;1;2".parse::<BlockRule>().is_err());
+        assert!("MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;16"
+            .parse::<BlockRule>()
+            .is_err());
+        assert!("MS,0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15"
+            .parse::<BlockRule>()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reversible() {
+        assert!(BlockRule::BILLIARD_BALL_MODEL.is_reversible());
+        assert!(BlockRule::CRITTERS.is_reversible());
+        let inverse = BlockRule::CRITTERS.inverse().unwrap();
+        assert!((0..16).all(|c| inverse.next(BlockRule::CRITTERS.next(c)) == c));
+
+        // every block becomes empty
+        let empty = BlockRule::new([0; 16]).unwrap();
+        assert!(!empty.is_reversible());
+        assert_eq!(empty.inverse(), None);
+    }
+
+    #[test]
+    fn test_billiard_ball() -> Result<()> {
+        let rule = Rule::margolus(BlockRule::BILLIARD_BALL_MODEL);
+        let mut world = World::new(10, 10, &[CELL_DEAD; 100])?.with_rule(rule);
+        world.set_present_cell(3, 3, CELL_ALIVE);
+        world.next();
+        assert_eq!(world.alive_cells().collect::<Vec<_>>(), [(4, 4)]);
+        world.next();
+        assert_eq!(world.alive_cells().collect::<Vec<_>>(), [(5, 5)]);
+        world.previous()?;
+        assert_eq!(world.alive_cells().collect::<Vec<_>>(), [(4, 4)]);
+        assert_eq!(world.generation(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_backward() -> Result<()> {
+        let soup = soup(21, 16, 3);
+        let mut world =
+            World::new(25, 20, &[CELL_DEAD; 500])?.with_rule(Rule::margolus(BlockRule::CRITTERS));
+        world.paste(&soup, 2, 2);
+        let initial = world.clone();
+        for _ in 0..31 {
+            world.next();
+        }
+        assert_ne!(world.present_cells(), initial.present_cells());
+        for _ in 0..31 {
+            world.previous()?;
+        }
+        assert_eq!(world.present_cells(), initial.present_cells());
+        assert_eq!(world.generation(), 0);
+        assert!(world.previous().is_err());
+
+        let empty = Rule::margolus(BlockRule::new([0; 16])?);
+        assert!(initial.clone().with_rule(empty).previous().is_err());
+        assert!(initial.with_rule(Rule::conway()).previous().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+//! Neighbourhoods of rules other than the Moore neighbourhood of the nearest 8 cells.
+//!
+//! The von Neumann and hexagonal neighbourhoods are subsets of the Moore neighbourhood, and
+//! step by the configuration of alive neighbours. Larger than Life neighbourhoods of a range
+//! count alive cells by a summed-area table of the world, as the sum of a few rectangles.
+
+use std::fmt;
+
+use crate::{World, CELL_ALIVE};
+
+/// Largest range of Larger than Life neighbourhoods
+pub const MAX_RANGE: u8 = 10;
+
+/// Cells whose alive cells count as the neighbours of a cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Neighbourhood {
+    /// The nearest 8 cells
+    #[default]
+    Moore,
+    /// The 4 cells N, W, E and S
+    VonNeumann,
+    /// The 6 cells of a hexagonal grid emulated on the square grid, the Moore neighbourhood
+    /// without NE and SW
+    Hexagonal,
+    /// Larger than Life neighbourhood of the cells within the range of 1 to [`MAX_RANGE`]
+    LargerThanLife {
+        range: u8,
+        shape: Shape,
+        /// whether the cell itself counts
+        middle: bool,
+    },
+}
+
+/// Shape of Larger than Life neighbourhoods.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Shape {
+    /// cells within the range along both axes, written as `NM`
+    Box,
+    /// cells within the range in the Manhattan distance, written as `NN`
+    Diamond,
+    /// cells within the Euclidean distance of the range plus 1/2, written as `NC`
+    Circle,
+}
+
+impl Neighbourhood {
+    /// Number of the cells counted as neighbours, including the cell itself if it counts
+    pub fn size(&self) -> u16 {
+        match self {
+            Neighbourhood::LargerThanLife {
+                range,
+                shape,
+                middle,
+            } => {
+                let cells: u16 = rows(*range, *shape).map(|(_, w)| 2 * w as u16 + 1).sum();
+                cells - !middle as u16
+            }
+            _ => self.mask().count_ones() as u16,
+        }
+    }
+
+    /// Bits of the neighbours in the configuration of the Moore neighbourhood,
+    /// in the order of NW, N, NE, W, E, SW, S and SE from the lowest bit
+    pub(crate) fn mask(&self) -> u8 {
+        match self {
+            Neighbourhood::Moore => 0b11111111,
+            Neighbourhood::VonNeumann => 0b01011010,
+            Neighbourhood::Hexagonal => 0b11011011,
+            Neighbourhood::LargerThanLife { .. } => 0,
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shape::Box => write!(f, "M"),
+            Shape::Diamond => write!(f, "N"),
+            Shape::Circle => write!(f, "C"),
+        }
+    }
+}
+
+/// Half widths of the rows of the shape from `-range` to `range` along the y-axis
+fn rows(range: u8, shape: Shape) -> impl Iterator<Item = (i32, u8)> {
+    let r = range as i32;
+    (-r..=r).map(move |dy| {
+        let half_width = match shape {
+            Shape::Box => r,
+            Shape::Diamond => r - dy.abs(),
+            Shape::Circle => {
+                (0..=r)
+                    .take_while(|dx| dx * dx + dy * dy <= r * r + r)
+                    .count() as i32
+                    - 1
+            }
+        };
+        (dy, half_width as u8)
+    })
+}
+
+/// Rectangles `(dy0, dy1, half width)` covering the shape, merging rows of the same width
+fn rectangles(range: u8, shape: Shape) -> Vec<(i32, i32, i32)> {
+    let mut rectangles: Vec<(i32, i32, i32)> = Vec::new();
+    for (dy, half_width) in rows(range, shape) {
+        match rectangles.last_mut() {
+            Some(last) if last.2 == half_width as i32 => last.1 = dy,
+            _ => rectangles.push((dy, dy, half_width as i32)),
+        }
+    }
+    rectangles
+}
+
+impl World {
+    /// Step the world by a rule of a Larger than Life neighbourhood
+    pub(crate) fn next_larger_than_life(&mut self, range: u8, shape: Shape, middle: bool) {
+        let (nx, ny) = (self.nx, self.ny);
+        // sums[(nx + 1) * y + x]: alive cells in [0, x) × [0, y)
+        let mut sums = vec![0u32; (nx + 1) * (ny + 1)];
+        for iy in 0..ny {
+            let mut row = 0;
+            for ix in 0..nx {
+                row += self.is_alive(self.present, ix, iy) as u32;
+                sums[(nx + 1) * (iy + 1) + ix + 1] = sums[(nx + 1) * iy + ix + 1] + row;
+            }
+        }
+        let sum = |x0: i32, y0: i32, x1: i32, y1: i32| {
+            let [x0, x1] = [x0, x1].map(|x| x.clamp(0, nx as i32) as usize);
+            let [y0, y1] = [y0, y1].map(|y| y.clamp(0, ny as i32) as usize);
+            let at = |x: usize, y: usize| sums[(nx + 1) * y + x];
+            at(x1, y1) + at(x0, y0) - at(x0, y1) - at(x1, y0)
+        };
+
+        let rectangles = rectangles(range, shape);
+        let next = 1 - self.present;
+        // ages of stochastic rules are counted after the noise
+        let count_ages = self.rule.stochastic().is_none();
+        for iy in 1..(ny - 1) {
+            for ix in 1..(nx - 1) {
+                let (x, y) = (ix as i32, iy as i32);
+                let mut count = rectangles
+                    .iter()
+                    .map(|&(dy0, dy1, w)| sum(x - w, y + dy0, x + w + 1, y + dy1 + 1))
+                    .sum::<u32>();
+                let present_cell = self.get_cell(self.present, ix, iy);
+                if !middle && present_cell == CELL_ALIVE {
+                    count -= 1;
+                }
+                let next_cell = self.rule.next_by_count(present_cell, count as u16);
+                self.update_cell(next, ix, iy, next_cell);
+                if count_ages {
+                    self.update_age(ix, iy, present_cell == next_cell);
+                }
+            }
+        }
+        self.generation += 1;
+        self.present = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{soup, Cell, Rule};
+
+    fn ltl(range: u8, shape: Shape, middle: bool) -> Neighbourhood {
+        Neighbourhood::LargerThanLife {
+            range,
+            shape,
+            middle,
+        }
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(Neighbourhood::Moore.size(), 8);
+        assert_eq!(Neighbourhood::VonNeumann.size(), 4);
+        assert_eq!(Neighbourhood::Hexagonal.size(), 6);
+        assert_eq!(ltl(1, Shape::Box, false).size(), 8);
+        assert_eq!(ltl(5, Shape::Box, true).size(), 121);
+        assert_eq!(ltl(2, Shape::Diamond, true).size(), 13);
+        // x² + y² <= r² + r
+        assert_eq!(ltl(1, Shape::Circle, false).size(), 8);
+        assert_eq!(ltl(2, Shape::Circle, true).size(), 21);
+        assert_eq!(ltl(10, Shape::Box, true).size(), 441);
+    }
+
+    /// Count alive neighbours cell by cell
+    fn naive_next(world: &World, neighbourhood: Neighbourhood) -> Vec<Cell> {
+        let Neighbourhood::LargerThanLife {
+            range,
+            shape,
+            middle,
+        } = neighbourhood
+        else {
+            unreachable!()
+        };
+        let mut cells = world.present_cells().to_vec();
+        for iy in 1..world.ny() - 1 {
+            for ix in 1..world.nx() - 1 {
+                let mut count = 0;
+                for (dy, w) in rows(range, shape) {
+                    for dx in -(w as i32)..=w as i32 {
+                        let (x, y) = (ix as i32 + dx, iy as i32 + dy);
+                        let inside = (0..world.nx() as i32).contains(&x)
+                            && (0..world.ny() as i32).contains(&y);
+                        if inside && (middle || (dx, dy) != (0, 0)) {
+                            count += (world.get_present_cell(x as usize, y as usize) == CELL_ALIVE)
+                                as u16;
+                        }
+                    }
+                }
+                let cell = world.get_present_cell(ix, iy);
+                cells[world.nx() * iy + ix] = world.rule().next_by_count(cell, count);
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_larger_than_life() -> anyhow::Result<()> {
+        let cells = soup(30, 20, 7).cells;
+        for text in [
+            "R3,C0,M1,S10..20,B12..16,NM",
+            "R4,C0,M0,S8..22,B9..12,NN",
+            "R3,C4,M0,S6..14,B7..9,NC",
+        ] {
+            let rule: Rule = text.parse()?;
+            let mut world = World::new(30, 20, &cells)?.with_rule(rule.clone());
+            for _ in 0..4 {
+                let expected = naive_next(&world, rule.neighbourhood());
+                world.next();
+                assert_eq!(world.present_cells(), expected, "{}", text);
+            }
+        }
+
+        // R1 of the box shape without the middle is Life
+        let mut life = World::new(30, 20, &cells)?;
+        let mut ltl = World::new(30, 20, &cells)?.with_rule("R1,C0,M0,S2..3,B3..3,NM".parse()?);
+        for _ in 0..10 {
+            life.next();
+            ltl.next();
+        }
+        assert_eq!(life.present_cells(), ltl.present_cells());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rectangles() {
+        assert_eq!(rectangles(3, Shape::Box), [(-3, 3, 3)]);
+        assert_eq!(
+            rectangles(1, Shape::Diamond),
+            [(-1, -1, 0), (0, 0, 1), (1, 1, 0)]
+        );
+        assert_eq!(
+            rectangles(2, Shape::Circle),
+            [(-2, -2, 1), (-1, 1, 2), (2, 2, 1)]
+        );
+    }
+}
@@ -0,0 +1,747 @@
+use std::fmt::Write;
+
+use anyhow::{Error, Result};
+
+use crate::{Cell, CELL_ALIVE, CELL_DEAD};
+
+/// A rectangular block of cells, read from or written to pattern files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pattern {
+    /// pattern size along with x-axis
+    pub width: usize,
+    /// pattern size along with y-axis
+    pub height: usize,
+    /// cells in row-major order
+    pub cells: Vec<Cell>,
+    /// pattern name (`#N` line of RLE, `!Name:` line of plaintext)
+    pub name: Option<String>,
+    /// rule string of RLE header, or a Golly rule file like [`Rule::to_definition`]
+    ///
+    /// [`Rule::to_definition`]: crate::Rule::to_definition
+    pub rule: Option<String>,
+}
+
+/// Maximum line length of RLE output
+const RLE_LINE_LENGTH: usize = 70;
+
+impl Pattern {
+    /// Create a new pattern
+    pub fn new(width: usize, height: usize, cells: &[Cell]) -> Result<Pattern> {
+        if cells.len() != width * height {
+            return Err(Error::msg("invalid cell size."));
+        }
+        Ok(Pattern {
+            width,
+            height,
+            cells: cells.to_vec(),
+            name: None,
+            rule: None,
+        })
+    }
+
+    #[inline]
+    pub fn get_cell(&self, ix: usize, iy: usize) -> Cell {
+        self.cells[self.width * iy + ix]
+    }
+
+    /// Rotate by 90 degrees clockwise
+    pub fn rotated_clockwise(&self) -> Pattern {
+        self.mapped(self.height, self.width, |ix, iy| (iy, self.height - 1 - ix))
+    }
+
+    /// Rotate by 90 degrees counterclockwise
+    pub fn rotated_counterclockwise(&self) -> Pattern {
+        self.mapped(self.height, self.width, |ix, iy| (self.width - 1 - iy, ix))
+    }
+
+    /// Mirror left and right
+    pub fn flipped_horizontally(&self) -> Pattern {
+        self.mapped(self.width, self.height, |ix, iy| (self.width - 1 - ix, iy))
+    }
+
+    /// Mirror top and bottom
+    pub fn flipped_vertically(&self) -> Pattern {
+        self.mapped(self.width, self.height, |ix, iy| (ix, self.height - 1 - iy))
+    }
+
+    /// New pattern of the size, whose cell (ix, iy) is the cell `source(ix, iy)` of this pattern
+    fn mapped(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Pattern {
+        let mut cells = Vec::with_capacity(width * height);
+        for iy in 0..height {
+            for ix in 0..width {
+                let (sx, sy) = source(ix, iy);
+                cells.push(self.get_cell(sx, sy));
+            }
+        }
+        Pattern {
+            width,
+            height,
+            cells,
+            name: self.name.clone(),
+            rule: self.rule.clone(),
+        }
+    }
+
+    /// Parse RLE, plaintext or macrocell, detected from the content
+    pub fn parse(text: &str) -> Result<Pattern> {
+        if text.trim_start().starts_with(MACROCELL_HEADER) {
+            return Self::from_macrocell(text);
+        }
+        let is_rle = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .is_some_and(|line| line.starts_with('x'));
+        match is_rle {
+            true => Self::from_rle(text),
+            false => Self::from_plaintext(text),
+        }
+    }
+
+    /// Parse the RLE format, with the multi-state cells of Generations rules written as
+    /// `.` for dead, `A` to `X` for states 1 to 24, and `pA` to `yO` for states 25 to 255.
+    /// A Golly rule file after the cells takes the place of the rule in the header.
+    ///
+    /// See <https://conwaylife.com/wiki/Run_Length_Encoded>
+    pub fn from_rle(text: &str) -> Result<Pattern> {
+        let mut name = None;
+        let mut header = None;
+        let mut lines = text.lines();
+        for line in lines.by_ref() {
+            let line = line.trim();
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some(n) = comment.strip_prefix('N') {
+                    name = Some(n.trim().to_string());
+                }
+            } else if !line.is_empty() {
+                header = Some(line);
+                break;
+            }
+        }
+        let header = header.ok_or(Error::msg("missing RLE header line."))?;
+        let (width, height, rule) = parse_rle_header(header)?;
+
+        let mut cells = vec![CELL_DEAD; width * height];
+        let (mut ix, mut iy) = (0usize, 0usize);
+        let mut count: Option<usize> = None;
+        let mut prefix: Option<char> = None;
+        let exceeded = || Error::msg("RLE cells exceed the size in header.");
+        'outer: for line in lines {
+            for c in line.chars() {
+                if prefix.is_some() && !c.is_ascii_uppercase() {
+                    return Err(Error::msg(format!("unexpected '{}' in RLE.", c)));
+                }
+                match c {
+                    '0'..='9' => {
+                        let digit = c.to_digit(10).unwrap() as usize;
+                        count = Some(
+                            count
+                                .unwrap_or(0)
+                                .checked_mul(10)
+                                .and_then(|n| n.checked_add(digit))
+                                .ok_or(Error::msg("too large run count in RLE."))?,
+                        );
+                    }
+                    'b' | '.' => {
+                        ix = ix
+                            .checked_add(count.take().unwrap_or(1))
+                            .filter(|&ix| ix <= width)
+                            .ok_or_else(exceeded)?;
+                    }
+                    '$' => {
+                        iy = iy
+                            .checked_add(count.take().unwrap_or(1))
+                            .filter(|&iy| iy <= height)
+                            .ok_or_else(exceeded)?;
+                        ix = 0;
+                    }
+                    '!' => break 'outer,
+                    c if c.is_whitespace() => {}
+                    'p'..='y' => prefix = Some(c),
+                    c if c.is_ascii_alphabetic() => {
+                        let cell = match (prefix.take(), c) {
+                            (None, 'A'..='X') => c as usize - 'A' as usize + 1,
+                            (Some(p), 'A'..='X') => {
+                                (p as usize - 'p' as usize + 1) * 24 + c as usize - 'A' as usize + 1
+                            }
+                            _ => CELL_ALIVE as usize,
+                        };
+                        let cell = Cell::try_from(cell)
+                            .map_err(|_| Error::msg(format!("too large cell state {}.", cell)))?;
+                        let end = ix
+                            .checked_add(count.take().unwrap_or(1))
+                            .filter(|&end| end <= width && iy < height)
+                            .ok_or_else(exceeded)?;
+                        cells[width * iy + ix..width * iy + end].fill(cell);
+                        ix = end;
+                    }
+                    c => return Err(Error::msg(format!("unexpected '{}' in RLE.", c))),
+                }
+            }
+        }
+        // '@' is not in the cells, so that a rule file can start only after them
+        let rule = match text.find("\n@RULE") {
+            Some(start) => Some(text[start + 1..].to_string()),
+            None => rule,
+        };
+        Ok(Pattern {
+            width,
+            height,
+            cells,
+            name,
+            rule,
+        })
+    }
+
+    /// Parse the plaintext (`.cells`) format
+    ///
+    /// See <https://conwaylife.com/wiki/Plaintext>
+    pub fn from_plaintext(text: &str) -> Result<Pattern> {
+        let mut name = None;
+        let mut rows = Vec::new();
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix('!') {
+                if let Some(n) = comment.strip_prefix("Name:") {
+                    name = Some(n.trim().to_string());
+                }
+                continue;
+            }
+            let row = line
+                .trim_end()
+                .chars()
+                .map(|c| match c {
+                    '.' => Ok(CELL_DEAD),
+                    'O' | 'o' | '*' => Ok(CELL_ALIVE),
+                    c => Err(Error::msg(format!("unexpected '{}' in plaintext.", c))),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            rows.push(row);
+        }
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+        if width
+            .checked_mul(height)
+            .is_none_or(|cells| cells > MAX_CELLS)
+        {
+            return Err(Error::msg("too large plaintext pattern."));
+        }
+        let mut cells = vec![CELL_DEAD; width * height];
+        for (iy, row) in rows.iter().enumerate() {
+            cells[width * iy..width * iy + row.len()].copy_from_slice(row);
+        }
+        Ok(Pattern {
+            width,
+            height,
+            cells,
+            name,
+            rule: None,
+        })
+    }
+
+    /// Parse the two-state macrocell format of Golly, trimmed to the bounding box of alive cells
+    ///
+    /// See <https://conwaylife.com/wiki/Macrocell>
+    pub fn from_macrocell(text: &str) -> Result<Pattern> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if !lines
+            .next()
+            .is_some_and(|line| line.starts_with(MACROCELL_HEADER))
+        {
+            return Err(Error::msg("missing macrocell header line."));
+        }
+        let (mut name, mut rule) = (None, None);
+        // node 0 is the empty node of any level
+        let mut nodes = vec![MacrocellNode {
+            level: 0,
+            population: 0,
+            kind: NodeKind::Leaf(Vec::new()),
+        }];
+        for line in lines {
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some(r) = comment.strip_prefix('R') {
+                    rule = Some(r.trim().to_string());
+                } else if let Some(n) = comment.strip_prefix('N') {
+                    name = Some(n.trim().to_string());
+                }
+                continue;
+            }
+            let node = match line.starts_with(['.', '*', '$']) {
+                true => parse_macrocell_leaf(line)?,
+                false => parse_macrocell_branch(line, &nodes)?,
+            };
+            nodes.push(node);
+        }
+        let root = nodes.last().expect("no empty node");
+        if root.population > MAX_CELLS as u64 {
+            return Err(Error::msg("too large macrocell pattern."));
+        }
+
+        let mut alive = Vec::with_capacity(root.population as usize);
+        collect_macrocell_cells(&nodes, nodes.len() - 1, 0, 0, &mut alive);
+        let x0 = alive.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let y0 = alive.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let width = alive.iter().map(|&(x, _)| x - x0 + 1).max().unwrap_or(0);
+        let height = alive.iter().map(|&(_, y)| y - y0 + 1).max().unwrap_or(0);
+        if width.saturating_mul(height) > MAX_CELLS as u64 {
+            return Err(Error::msg("too large macrocell pattern."));
+        }
+        let (width, height) = (width as usize, height as usize);
+        let mut cells = vec![CELL_DEAD; width * height];
+        for (x, y) in alive {
+            cells[width * (y - y0) as usize + (x - x0) as usize] = CELL_ALIVE;
+        }
+        Ok(Pattern {
+            width,
+            height,
+            cells,
+            name,
+            rule,
+        })
+    }
+
+    /// Write in the RLE format, with multi-state cells if there are cells of dying states
+    pub fn to_rle(&self) -> String {
+        let mut text = String::new();
+        if let Some(name) = &self.name {
+            writeln!(text, "#N {}", name).unwrap();
+        }
+        write!(text, "x = {}, y = {}", self.width, self.height).unwrap();
+        // rule files are named in the header like Golly, and follow the cells
+        let rule_file = self
+            .rule
+            .as_deref()
+            .filter(|rule| rule.starts_with("@RULE"));
+        if let Some(rule) = &self.rule {
+            let name = match rule_file {
+                Some(file) => file["@RULE".len()..].lines().next().unwrap_or("").trim(),
+                None => rule,
+            };
+            write!(text, ", rule = {}", name).unwrap();
+        }
+        text.push('\n');
+
+        let multi_state = self.cells.iter().any(|&c| c > CELL_ALIVE);
+        let mut tokens = Vec::new();
+        let mut pending_rows = 0;
+        for iy in 0..self.height {
+            let row = &self.cells[self.width * iy..self.width * (iy + 1)];
+            let len = row
+                .iter()
+                .rposition(|&c| c != CELL_DEAD)
+                .map_or(0, |n| n + 1);
+            if len > 0 && pending_rows > 0 {
+                tokens.push(run_token(pending_rows, "$"));
+                pending_rows = 0;
+            }
+            let mut ix = 0;
+            while ix < len {
+                let run = row[ix..len].iter().take_while(|&&c| c == row[ix]).count();
+                let tag = match (multi_state, row[ix]) {
+                    (false, CELL_DEAD) => "b".to_string(),
+                    (false, _) => "o".to_string(),
+                    (true, cell) => multi_state_tag(cell),
+                };
+                tokens.push(run_token(run, &tag));
+                ix += run;
+            }
+            pending_rows += 1;
+        }
+        tokens.push("!".to_string());
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length + token.len() > RLE_LINE_LENGTH {
+                text.push('\n');
+                line_length = 0;
+            }
+            line_length += token.len();
+            text.push_str(&token);
+        }
+        text.push('\n');
+        if let Some(file) = rule_file {
+            text.push_str(file);
+            if !file.ends_with('\n') {
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    /// Write in the plaintext (`.cells`) format, where dying cells are dead
+    pub fn to_plaintext(&self) -> String {
+        let mut text = String::new();
+        if let Some(name) = &self.name {
+            writeln!(text, "!Name: {}", name).unwrap();
+        }
+        for iy in 0..self.height {
+            for ix in 0..self.width {
+                text.push(match self.get_cell(ix, iy) {
+                    CELL_ALIVE => 'O',
+                    _ => '.',
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// First line of macrocell
+const MACROCELL_HEADER: &str = "[M2]";
+
+/// Maximum cells of a pattern read from a file, whose header, blank lines or quadtree may span
+/// a huge area
+const MAX_CELLS: usize = 1 << 26;
+
+/// Level of the 8x8 leaves of macrocell
+const MACROCELL_LEAF_LEVEL: u32 = 3;
+
+/// Node of the quadtree in macrocell, whose size is `2^level`.
+struct MacrocellNode {
+    level: u32,
+    population: u64,
+    kind: NodeKind,
+}
+
+enum NodeKind {
+    /// alive cells of an 8x8 block
+    Leaf(Vec<(u8, u8)>),
+    /// node indices of the north-west, north-east, south-west and south-east quadrants
+    Branch([usize; 4]),
+}
+
+/// Parse a leaf line like `.*$..*$***$`
+fn parse_macrocell_leaf(line: &str) -> Result<MacrocellNode> {
+    let mut alive = Vec::new();
+    let (mut ix, mut iy) = (0, 0);
+    for c in line.chars() {
+        match c {
+            '.' => ix += 1,
+            '*' => {
+                if ix >= 8 || iy >= 8 {
+                    return Err(Error::msg("macrocell leaf exceeds 8x8 cells."));
+                }
+                alive.push((ix, iy));
+                ix += 1;
+            }
+            '$' => {
+                ix = 0;
+                iy += 1;
+            }
+            c => return Err(Error::msg(format!("unexpected '{}' in macrocell.", c))),
+        }
+    }
+    Ok(MacrocellNode {
+        level: MACROCELL_LEAF_LEVEL,
+        population: alive.len() as u64,
+        kind: NodeKind::Leaf(alive),
+    })
+}
+
+/// Parse a branch line `level nw ne sw se` referring to the previous nodes
+fn parse_macrocell_branch(line: &str, nodes: &[MacrocellNode]) -> Result<MacrocellNode> {
+    let invalid = || Error::msg(format!("invalid macrocell node '{}'.", line));
+    let numbers = line
+        .split_whitespace()
+        .map(|n| n.parse::<usize>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>>>()?;
+    let [level, nw, ne, sw, se] = numbers[..] else {
+        return Err(invalid());
+    };
+    // coordinates of cells must fit in u64
+    let level = u32::try_from(level)
+        .ok()
+        .filter(|&level| level < u64::BITS)
+        .ok_or_else(invalid)?;
+    if level <= MACROCELL_LEAF_LEVEL {
+        return Err(Error::msg("multi-state macrocell is not supported."));
+    }
+    let children = [nw, ne, sw, se];
+    let mut population = 0u64;
+    for &child in &children {
+        let node = nodes.get(child).ok_or_else(invalid)?;
+        if child != 0 && node.level != level - 1 {
+            return Err(invalid());
+        }
+        population = population.saturating_add(node.population);
+    }
+    Ok(MacrocellNode {
+        level,
+        population,
+        kind: NodeKind::Branch(children),
+    })
+}
+
+/// Push the alive cells of the node with its top-left corner at (x, y)
+fn collect_macrocell_cells(
+    nodes: &[MacrocellNode],
+    index: usize,
+    x: u64,
+    y: u64,
+    alive: &mut Vec<(u64, u64)>,
+) {
+    let node = &nodes[index];
+    if node.population == 0 {
+        return;
+    }
+    match &node.kind {
+        NodeKind::Leaf(cells) => {
+            alive.extend(cells.iter().map(|&(ix, iy)| (x + ix as u64, y + iy as u64)));
+        }
+        NodeKind::Branch(children) => {
+            let half = 1 << (node.level - 1);
+            let offsets = [(0, 0), (half, 0), (0, half), (half, half)];
+            for (&child, (dx, dy)) in children.iter().zip(offsets) {
+                collect_macrocell_cells(nodes, child, x + dx, y + dy, alive);
+            }
+        }
+    }
+}
+
+/// Tag of the cell state in multi-state RLE
+fn multi_state_tag(cell: Cell) -> String {
+    let letter = |n: u8| char::from(b'A' + n);
+    match cell {
+        CELL_DEAD => ".".to_string(),
+        _ => match (cell - 1) / 24 {
+            0 => letter(cell - 1).to_string(),
+            q => format!("{}{}", char::from(b'p' + q - 1), letter((cell - 1) % 24)),
+        },
+    }
+}
+
+fn run_token(count: usize, tag: &str) -> String {
+    match count {
+        1 => tag.to_string(),
+        n => format!("{}{}", n, tag),
+    }
+}
+
+fn parse_rle_header(header: &str) -> Result<(usize, usize, Option<String>)> {
+    let (mut width, mut height, mut rule) = (None, None, None);
+    // the rule is the last item, and may have commas like `R5,C0,M1,S34..58,B34..45,NM`
+    let (sizes, rule_item) = match header.find("rule") {
+        Some(index) => (
+            header[..index].trim_end().trim_end_matches(','),
+            &header[index..],
+        ),
+        None => (header, ""),
+    };
+    for item in sizes
+        .split(',')
+        .chain(Some(rule_item).filter(|item| !item.is_empty()))
+    {
+        let (key, value) = item
+            .split_once('=')
+            .ok_or(Error::msg(format!("invalid RLE header '{}'.", header)))?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = value.parse::<usize>().ok(),
+            "y" => height = value.parse::<usize>().ok(),
+            "rule" => rule = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_CELLS => Ok((width, height, rule)),
+            _ => Err(Error::msg("too large RLE pattern.")),
+        },
+        _ => Err(Error::msg(format!("invalid RLE header '{}'.", header))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const O: Cell = CELL_ALIVE;
+    const B: Cell = CELL_DEAD;
+
+    fn glider() -> Pattern {
+        Pattern::new(3, 3, &[[B, O, B], [B, B, O], [O, O, O]].concat()).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_new() {
+        assert!(Pattern::new(2, 2, &[O; 3]).is_err());
+        assert!(Pattern::new(2, 2, &[O; 4]).is_ok());
+    }
+
+    #[test]
+    fn test_transform() {
+        let pattern = Pattern::new(3, 2, &[[O, O, O], [O, B, B]].concat()).unwrap();
+        assert_eq!(
+            pattern.rotated_clockwise().cells,
+            [[O, O], [B, O], [B, O]].concat()
+        );
+        assert_eq!(
+            pattern.rotated_counterclockwise().cells,
+            [[O, B], [O, B], [O, O]].concat()
+        );
+        assert_eq!(
+            pattern.flipped_horizontally().cells,
+            [[O, O, O], [B, B, O]].concat()
+        );
+        assert_eq!(
+            pattern.flipped_vertically().cells,
+            [[O, B, B], [O, O, O]].concat()
+        );
+        let rotated = pattern.rotated_clockwise();
+        assert_eq!((rotated.width, rotated.height), (2, 3));
+        assert_eq!(rotated.rotated_counterclockwise(), pattern);
+        assert_eq!(
+            glider().flipped_horizontally().flipped_horizontally(),
+            glider()
+        );
+    }
+
+    #[test]
+    fn test_from_rle() {
+        let pattern =
+            Pattern::from_rle("#N Glider\n#C comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n")
+                .unwrap();
+        assert_eq!(pattern.cells, glider().cells);
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+        let pattern =
+            Pattern::from_rle("x = 1, y = 1, rule = R5,C0,M1,S34..58,B34..45,NM\no!").unwrap();
+        assert_eq!(pattern.rule.as_deref(), Some("R5,C0,M1,S34..58,B34..45,NM"));
+
+        // multi-line data and blank rows
+        let pattern = Pattern::from_rle("x = 4, y = 4\no2$\n3bo\n!").unwrap();
+        assert_eq!(
+            pattern.cells,
+            [[O, B, B, B], [B, B, B, B], [B, B, B, O], [B, B, B, B]].concat()
+        );
+
+        assert!(Pattern::from_rle("bo$2bo$3o!").is_err());
+        assert!(Pattern::from_rle("x = 2, y = 2\n3o!").is_err());
+        assert!(Pattern::from_rle("x = 2, y = 2\no?!").is_err());
+        assert!(Pattern::from_rle("x = 100000000, y = 100000000\no!").is_err());
+        assert!(Pattern::from_rle(&format!("x = {}, y = 2\no!", usize::MAX)).is_err());
+        // huge runs
+        assert!(Pattern::from_rle(&format!("x = 2, y = 1\n{}b2o!", usize::MAX)).is_err());
+        assert!(Pattern::from_rle(&format!("x = 2, y = 1\n{}$2o!", usize::MAX)).is_err());
+        assert!(Pattern::from_rle("x = 2, y = 2\n3b!").is_err());
+    }
+
+    #[test]
+    fn test_from_plaintext() {
+        let pattern = Pattern::from_plaintext("!Name: Glider\n!\n.O\n..O\nOOO\n").unwrap();
+        assert_eq!(pattern.cells, glider().cells);
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+
+        assert!(Pattern::from_plaintext(".O\nXX\n").is_err());
+        // a long line and many blank lines
+        let huge = format!("{}\n{}O\n", ".".repeat(1 << 14), "\n".repeat(1 << 13));
+        assert!(Pattern::from_plaintext(&huge).is_err());
+    }
+
+    #[test]
+    fn test_from_macrocell() {
+        // a glider in the south-east quadrant of a 16x16 node
+        let text = "[M2] (golly 4.2)\n#R B3/S23\n.*$..*$***$\n4 0 0 0 1\n";
+        let pattern = Pattern::from_macrocell(text).unwrap();
+        assert_eq!(pattern.cells, glider().cells);
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+
+        // shared nodes are expanded at each place
+        let text = "[M2]\n*$\n4 1 0 0 1\n5 2 2 0 0\n";
+        let pattern = Pattern::from_macrocell(text).unwrap();
+        assert_eq!((pattern.width, pattern.height), (25, 9));
+        assert_eq!(pattern.cells.iter().filter(|&&c| c == O).count(), 4);
+        assert_eq!(pattern.get_cell(24, 8), O);
+
+        let empty = Pattern::from_macrocell("[M2]\n4 0 0 0 0\n").unwrap();
+        assert_eq!((empty.width, empty.height), (0, 0));
+
+        assert!(Pattern::from_macrocell("4 0 0 0 0\n").is_err());
+        assert!(Pattern::from_macrocell("[M2]\n*********$\n").is_err());
+        assert!(Pattern::from_macrocell("[M2]\n*$\n5 1 0 0 0\n").is_err());
+        assert!(Pattern::from_macrocell("[M2]\n*$\n4 2 0 0 0\n").is_err());
+        assert!(Pattern::from_macrocell("[M2]\n1 0 0 0 1\n").is_err());
+        // 2^40 x 2^40 cells
+        let huge = "[M2]\n*$\n4 1 0 0 0\n".to_string()
+            + &(5..=40)
+                .map(|level| format!("{} {} 0 0 {}\n", level, level - 3, level - 3))
+                .collect::<String>();
+        assert!(Pattern::from_macrocell(&huge).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Pattern::parse("x = 3, y = 3\nbo$2bo$3o!").unwrap().cells,
+            glider().cells
+        );
+        assert_eq!(
+            Pattern::parse(".O\n..O\nOOO\n").unwrap().cells,
+            glider().cells
+        );
+        assert_eq!(
+            Pattern::parse("[M2]\n.*$..*$***$\n").unwrap().cells,
+            glider().cells
+        );
+    }
+
+    #[test]
+    fn test_to_rle() {
+        assert_eq!(glider().to_rle(), "x = 3, y = 3\nbo$2bo$3o!\n");
+
+        let pattern = Pattern::new(
+            4,
+            4,
+            &[[O, B, B, B], [B, B, B, B], [B, B, B, O], [B, B, B, B]].concat(),
+        )
+        .unwrap();
+        assert_eq!(pattern.to_rle(), "x = 4, y = 4\no2$3bo!\n");
+
+        let wide = Pattern::new(200, 1, &[O, B].repeat(100)).unwrap();
+        let rle = wide.to_rle();
+        assert!(rle.lines().all(|line| line.len() <= RLE_LINE_LENGTH));
+        assert_eq!(Pattern::from_rle(&rle).unwrap(), wide);
+    }
+
+    #[test]
+    fn test_multi_state_rle() {
+        let pattern = Pattern::from_rle("x = 5, y = 2, rule = /2/3\n.AB2.$pA.yO!").unwrap();
+        assert_eq!(pattern.cells, [0, 1, 2, 0, 0, 25, 0, 255, 0, 0]);
+        assert_eq!(pattern.to_rle(), "x = 5, y = 2, rule = /2/3\n.AB$pA.yO!\n");
+        assert_eq!(Pattern::from_rle(&pattern.to_rle()).unwrap(), pattern);
+        assert_eq!(pattern.to_plaintext(), ".O...\n.....\n");
+
+        assert!(Pattern::from_rle("x = 1, y = 1\nyP!").is_err());
+        assert!(Pattern::from_rle("x = 1, y = 1\np2A!").is_err());
+    }
+
+    #[test]
+    fn test_rule_file_rle() {
+        let table = "@RULE Turn\n@TABLE\nn_states:3\nneighborhood:vonNeumann\n\
+                     symmetries:none\n0,1,0,0,0,2\n";
+        let mut pattern = Pattern::from_rle("x = 2, y = 1\nAB!").unwrap();
+        pattern.rule = Some(table.to_string());
+        let rle = pattern.to_rle();
+        assert_eq!(rle, format!("x = 2, y = 1, rule = Turn\nAB!\n{}", table));
+        assert_eq!(Pattern::from_rle(&rle).unwrap(), pattern);
+        assert_eq!(Pattern::parse(&rle).unwrap(), pattern);
+    }
+
+    #[test]
+    fn test_to_plaintext() {
+        let mut pattern = glider();
+        pattern.name = Some("Glider".to_string());
+        let text = pattern.to_plaintext();
+        assert_eq!(text, "!Name: Glider\n.O.\n..O\nOOO\n");
+        assert_eq!(Pattern::from_plaintext(&text).unwrap(), pattern);
+    }
+}
@@ -0,0 +1,914 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{Error, Result};
+
+use crate::margolus::BlockRule;
+use crate::neighbourhood::{Neighbourhood, Shape, MAX_RANGE};
+use crate::stochastic::Stochastic;
+use crate::table::{RuleTable, WIREWORLD};
+use crate::{Cell, CELL_ALIVE, CELL_DEAD};
+
+/// Life-like rule deciding the next cell by the alive neighbours in the Moore neighbourhood.
+///
+/// Isotropic non-totalistic rules decide by the configuration of the alive neighbours, a bit per
+/// neighbour in the order of NW, N, NE, W, E, SW, S and SE from the lowest bit, and are written
+/// in Hensel notation like `B2-a/S12`. Totalistic rules decide only by the number of them.
+///
+/// Rules of the Generations family have more than 2 states: an alive cell which does not survive
+/// decays through the dying states `2..states` before it becomes dead, and dying cells
+/// neither count as alive neighbours nor come back to life.
+///
+/// Rules of other neighbourhoods decide by the number of alive neighbours, written with a suffix
+/// like `B2/S34H` for the hexagonal and `B2/S013V` for the von Neumann neighbourhood, or in the
+/// Larger than Life notation like `R5,C0,M1,S34..58,B34..45,NM`.
+///
+/// Rules of rule tables, like [`Rule::wireworld`], decide by the states of the neighbours and
+/// are written by their names, and block rules of the Margolus neighbourhood replace 2x2 blocks
+/// like `MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15`.
+///
+/// Stochastic rules follow a rule with the probabilities of births, survivals and random flips
+/// like `B3/S23~birth=0.9,survival=0.95,temperature=0.001,seed=42`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// `birth[c]`: a dead cell with the configuration `c` of alive neighbours becomes alive
+    birth: [bool; 256],
+    /// `survival[c]`: an alive cell with the configuration `c` of alive neighbours stays alive
+    survival: [bool; 256],
+    /// number of states including dead and alive, 2 for life-like rules
+    states: u8,
+    /// whether every configuration with the same number of alive neighbours has the same result
+    totalistic: bool,
+    neighbourhood: Neighbourhood,
+    /// numbers of alive neighbours for birth in Larger than Life neighbourhoods
+    birth_range: (u16, u16),
+    /// numbers of alive neighbours for survival in Larger than Life neighbourhoods
+    survival_range: (u16, u16),
+    /// rule table deciding instead of the birth and survival
+    table: Option<Arc<RuleTable>>,
+    /// rule of 2x2 blocks replacing instead of the birth and survival
+    block: Option<BlockRule>,
+    /// probabilities applied to the births and survivals
+    stochastic: Option<Stochastic>,
+}
+
+/// Range of no numbers of alive neighbours, for rules other than Larger than Life
+const EMPTY_RANGE: (u16, u16) = (1, 0);
+
+/// Hensel letters of the configurations of 1 to 4 alive neighbours, with a configuration
+/// of each letter. Configurations of 5 to 7 take the letters of their complements.
+const HENSEL_LETTERS: [&[(char, u8)]; 4] = [
+    &[('c', 0b00000001), ('e', 0b00000010)],
+    &[
+        ('c', 0b00000101),
+        ('e', 0b00001010),
+        ('a', 0b00000011),
+        ('i', 0b00011000),
+        ('k', 0b00010001),
+        ('n', 0b00100100),
+    ],
+    &[
+        ('c', 0b00100101),
+        ('e', 0b00011010),
+        ('a', 0b00001011),
+        ('i', 0b00000111),
+        ('k', 0b00110010),
+        ('n', 0b00001101),
+        ('j', 0b00001110),
+        ('q', 0b00100110),
+        ('r', 0b00011001),
+        ('y', 0b00110001),
+    ],
+    &[
+        ('c', 0b10100101),
+        ('e', 0b01011010),
+        ('a', 0b00001111),
+        ('i', 0b00011101),
+        ('k', 0b00110011),
+        ('n', 0b00100111),
+        ('j', 0b00111010),
+        ('q', 0b00110110),
+        ('r', 0b00011011),
+        ('y', 0b00110101),
+        ('t', 0b00111001),
+        ('w', 0b00101110),
+        ('z', 0b00111100),
+    ],
+];
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl Rule {
+    /// Create a rule from the numbers of alive neighbours for birth and survival
+    pub fn new(birth: &[u8], survival: &[u8]) -> Result<Rule> {
+        Rule::generations(birth, survival, 2)
+    }
+
+    /// Create a rule of the Generations family with the number of states,
+    /// which is the life-like rule for 2 states
+    pub fn generations(birth: &[u8], survival: &[u8], states: u8) -> Result<Rule> {
+        Rule::with_neighbourhood(birth, survival, states, Neighbourhood::Moore)
+    }
+
+    /// Create a rule deciding by the number of alive neighbours in the Moore, von Neumann or
+    /// hexagonal neighbourhood
+    pub fn with_neighbourhood(
+        birth: &[u8],
+        survival: &[u8],
+        states: u8,
+        neighbourhood: Neighbourhood,
+    ) -> Result<Rule> {
+        if let Neighbourhood::LargerThanLife { .. } = neighbourhood {
+            return Err(Error::msg("Larger than Life rules need ranges of counts."));
+        }
+        let mut tables = [[false; 9]; 2];
+        for (counts, table) in [birth, survival].into_iter().zip(&mut tables) {
+            for &n in counts {
+                if n as u16 > neighbourhood.size() {
+                    return Err(Error::msg(format!("invalid neighbour count {}.", n)));
+                }
+                table[n as usize] = true;
+            }
+        }
+        let [birth, survival] = tables.map(|counts| count_table(counts, neighbourhood.mask()));
+        let mut rule = Rule::with_tables(birth, survival, states)?;
+        rule.neighbourhood = neighbourhood;
+        Ok(rule)
+    }
+
+    /// Create a rule of a Larger than Life neighbourhood with the ranges of the numbers of
+    /// alive neighbours for birth and survival
+    pub fn larger_than_life(
+        neighbourhood: Neighbourhood,
+        birth: RangeInclusive<u16>,
+        survival: RangeInclusive<u16>,
+        states: u8,
+    ) -> Result<Rule> {
+        let Neighbourhood::LargerThanLife { range, .. } = neighbourhood else {
+            return Err(Error::msg("not a Larger than Life neighbourhood."));
+        };
+        if !(1..=MAX_RANGE).contains(&range) {
+            return Err(Error::msg(format!("invalid range {}.", range)));
+        }
+        for counts in [&birth, &survival] {
+            if counts.is_empty() || *counts.end() > neighbourhood.size() {
+                return Err(Error::msg(format!(
+                    "invalid neighbour counts {}..{}.",
+                    counts.start(),
+                    counts.end()
+                )));
+            }
+        }
+        let mut rule = Rule::with_tables([false; 256], [false; 256], states)?;
+        rule.neighbourhood = neighbourhood;
+        rule.totalistic = false;
+        rule.birth_range = (*birth.start(), *birth.end());
+        rule.survival_range = (*survival.start(), *survival.end());
+        Ok(rule)
+    }
+
+    /// Create a rule of the results of every configuration of alive neighbours
+    /// in the Moore neighbourhood
+    fn with_tables(birth: [bool; 256], survival: [bool; 256], states: u8) -> Result<Rule> {
+        if states < 2 {
+            return Err(Error::msg(format!("invalid number of states {}.", states)));
+        }
+        let totalistic = [birth, survival]
+            .iter()
+            .all(|table| table == &count_table(count_results(table, u8::MAX), u8::MAX));
+        Ok(Rule {
+            birth,
+            survival,
+            states,
+            totalistic,
+            neighbourhood: Neighbourhood::Moore,
+            birth_range: EMPTY_RANGE,
+            survival_range: EMPTY_RANGE,
+            table: None,
+            block: None,
+            stochastic: None,
+        })
+    }
+
+    /// Create a rule of a shared rule table
+    pub(crate) fn with_table(table: Arc<RuleTable>) -> Rule {
+        Rule {
+            birth: [false; 256],
+            survival: [false; 256],
+            states: table.states(),
+            totalistic: false,
+            neighbourhood: table.neighbourhood(),
+            birth_range: EMPTY_RANGE,
+            survival_range: EMPTY_RANGE,
+            table: Some(table),
+            block: None,
+            stochastic: None,
+        }
+    }
+
+    /// Create a rule of a block rule
+    pub(crate) fn with_block(block: BlockRule) -> Rule {
+        Rule {
+            block: Some(block),
+            ..Rule::conway()
+        }
+    }
+
+    /// B3/S23, Conway's Game of Life
+    pub const fn conway() -> Rule {
+        let mut birth = [false; 9];
+        let mut survival = [false; 9];
+        birth[3] = true;
+        survival[2] = true;
+        survival[3] = true;
+        Rule {
+            birth: count_table(birth, u8::MAX),
+            survival: count_table(survival, u8::MAX),
+            states: 2,
+            totalistic: true,
+            neighbourhood: Neighbourhood::Moore,
+            birth_range: EMPTY_RANGE,
+            survival_range: EMPTY_RANGE,
+            table: None,
+            block: None,
+            stochastic: None,
+        }
+    }
+
+    /// The next state of the cell with the number of alive neighbours,
+    /// for totalistic rules of the Moore neighbourhood.
+    /// The number is at most 8, and the cell is dead for larger numbers.
+    #[inline]
+    pub fn next(&self, cell: Cell, alive_neighbours: u8) -> Cell {
+        if alive_neighbours > 8 {
+            return CELL_DEAD;
+        }
+        self.next_by_configuration(cell, ((1u16 << alive_neighbours) - 1) as u8)
+    }
+
+    /// The next state of the cell with the configuration of alive neighbours
+    /// in the Moore neighbourhood, for rules other than Larger than Life
+    #[inline]
+    pub fn next_by_configuration(&self, cell: Cell, configuration: u8) -> Cell {
+        let configuration = configuration as usize;
+        self.transition(
+            cell,
+            self.birth[configuration],
+            self.survival[configuration],
+        )
+    }
+
+    /// The next state of the cell with the number of alive neighbours,
+    /// for Larger than Life rules
+    #[inline]
+    pub fn next_by_count(&self, cell: Cell, count: u16) -> Cell {
+        let within = |(min, max): (u16, u16)| min <= count && count <= max;
+        self.transition(cell, within(self.birth_range), within(self.survival_range))
+    }
+
+    #[inline]
+    fn transition(&self, cell: Cell, birth: bool, survival: bool) -> Cell {
+        match cell {
+            CELL_DEAD if birth => CELL_ALIVE,
+            CELL_DEAD => CELL_DEAD,
+            CELL_ALIVE if survival => CELL_ALIVE,
+            _ => match cell.saturating_add(1) {
+                next if next < self.states => next,
+                _ => CELL_DEAD,
+            },
+        }
+    }
+
+    /// Numbers of alive neighbours for birth in every configuration
+    pub fn birth(&self) -> impl Iterator<Item = u16> + '_ {
+        self.counts(&self.birth, self.birth_range)
+    }
+
+    /// Numbers of alive neighbours for survival in every configuration
+    pub fn survival(&self) -> impl Iterator<Item = u16> + '_ {
+        self.counts(&self.survival, self.survival_range)
+    }
+
+    /// Numbers of alive neighbours whose every configuration is true in the table,
+    /// or within the range for Larger than Life rules
+    fn counts(&self, table: &[bool; 256], range: (u16, u16)) -> impl Iterator<Item = u16> {
+        let counts = count_results(table, self.neighbourhood.mask());
+        let size = self.neighbourhood.size();
+        let neighbourhood = self.neighbourhood;
+        (0..=size).filter(move |&n| match neighbourhood {
+            Neighbourhood::LargerThanLife { .. } => range.0 <= n && n <= range.1,
+            _ => counts[n as usize],
+        })
+    }
+
+    /// Number of states including dead and alive, more than 2 for Generations rules
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Whether the rule decides only by the number of alive neighbours
+    /// in the Moore neighbourhood
+    pub fn is_totalistic(&self) -> bool {
+        self.totalistic
+    }
+
+    /// Neighbourhood whose alive cells count as neighbours
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// Rule table deciding the next cells, if the rule is of a rule table
+    pub fn table(&self) -> Option<&RuleTable> {
+        self.table.as_deref()
+    }
+
+    pub(crate) fn shared_table(&self) -> Option<Arc<RuleTable>> {
+        self.table.clone()
+    }
+
+    /// Rule of 2x2 blocks, if the rule is of the Margolus neighbourhood
+    pub fn block(&self) -> Option<&BlockRule> {
+        self.block.as_ref()
+    }
+
+    /// The rule written to be parsed back: the rule file of rule tables other than the
+    /// built-in ones, whose names are unknown to [`FromStr`], or the notation of other rules.
+    /// RLE written by [`Pattern::to_rle`](crate::Pattern::to_rle) keeps the rule file after
+    /// the cells.
+    pub fn to_definition(&self) -> String {
+        match &self.table {
+            Some(table) if table.source() != WIREWORLD => table.source().to_string(),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Probabilities of births, survivals and random flips, if the rule is stochastic
+    pub fn stochastic(&self) -> Option<&Stochastic> {
+        self.stochastic.as_ref()
+    }
+
+    pub(crate) fn with_checked_stochastic(self, stochastic: Stochastic) -> Rule {
+        Rule {
+            stochastic: Some(stochastic),
+            ..self
+        }
+    }
+}
+
+/// Table of configurations with the results of the numbers of alive neighbours in the mask
+const fn count_table(counts: [bool; 9], mask: u8) -> [bool; 256] {
+    let mut table = [false; 256];
+    let mut configuration = 0;
+    while configuration < 256 {
+        table[configuration] = counts[(configuration as u8 & mask).count_ones() as usize];
+        configuration += 1;
+    }
+    table
+}
+
+/// Numbers of alive neighbours in the mask whose every configuration is true in the table
+fn count_results(table: &[bool; 256], mask: u8) -> [bool; 9] {
+    let mut counts = [true; 9];
+    for (configuration, &result) in table.iter().enumerate() {
+        counts[(configuration as u8 & mask).count_ones() as usize] &= result;
+    }
+    counts
+}
+
+/// Hensel letter of every configuration, none for 0 and 8 alive neighbours
+fn hensel_letters() -> [Option<char>; 256] {
+    // neighbours moved by rotating clockwise and by flipping horizontally,
+    // in the order of NW, N, NE, W, E, SW, S and SE
+    const ROTATION: [u8; 8] = [2, 4, 7, 1, 6, 0, 3, 5];
+    const FLIP: [u8; 8] = [2, 1, 0, 4, 3, 7, 6, 5];
+    let map = |configuration: u8, to: &[u8; 8]| {
+        (0..8)
+            .filter(|&i| configuration >> i & 1 == 1)
+            .fold(0u8, |mapped, i| mapped | 1 << to[i])
+    };
+
+    let mut letters = [None; 256];
+    for classes in HENSEL_LETTERS {
+        for &(letter, mut configuration) in classes {
+            for _ in 0..4 {
+                configuration = map(configuration, &ROTATION);
+                for symmetric in [configuration, map(configuration, &FLIP)] {
+                    letters[symmetric as usize] = Some(letter);
+                    // 4 alive neighbours are their own complements
+                    if symmetric.count_ones() < 4 {
+                        letters[!symmetric as usize] = Some(letter);
+                    }
+                }
+            }
+        }
+    }
+    letters
+}
+
+/// Hensel letters of the number of alive neighbours in the canonical order
+fn letters_of(n: u32) -> impl Iterator<Item = char> {
+    let classes = match n {
+        1..=4 => HENSEL_LETTERS[n as usize - 1],
+        5..=7 => HENSEL_LETTERS[7 - n as usize],
+        _ => &[],
+    };
+    classes.iter().map(|&(letter, _)| letter)
+}
+
+/// Parse neighbour conditions in Hensel notation like `2-a3` or `1e2ck`
+fn parse_conditions(text: &str, letters: &[Option<char>; 256]) -> Result<[bool; 256]> {
+    let mut table = [false; 256];
+    let text = text.to_lowercase();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let n = match c.to_digit(10) {
+            Some(n) if n <= 8 => n,
+            _ => return Err(Error::msg(format!("invalid neighbour count '{}'.", c))),
+        };
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut chosen = Vec::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_lowercase) {
+            if !letters_of(n).any(|l| l == letter) {
+                return Err(Error::msg(format!("invalid letter '{}' of {}.", letter, n)));
+            }
+            chosen.push(letter);
+        }
+        if negated && chosen.is_empty() {
+            return Err(Error::msg(format!("missing letters after '{}-'.", n)));
+        }
+        for configuration in (0..=255u8).filter(|c| c.count_ones() == n) {
+            let letter = letters[configuration as usize];
+            let chosen = chosen.is_empty() || letter.is_some_and(|l| chosen.contains(&l));
+            table[configuration as usize] |= chosen != negated;
+        }
+    }
+    Ok(table)
+}
+
+/// Write neighbour conditions in Hensel notation, with the fewer letters of either the chosen
+/// configurations or the others
+fn write_conditions(
+    f: &mut fmt::Formatter<'_>,
+    table: &[bool; 256],
+    letters: &[Option<char>; 256],
+) -> fmt::Result {
+    for n in 0..=8 {
+        let configurations = (0..=255u8).filter(|c| c.count_ones() == n);
+        let (chosen, others): (Vec<_>, Vec<_>) = configurations.partition(|&c| table[c as usize]);
+        if chosen.is_empty() {
+            continue;
+        }
+        write!(f, "{}", n)?;
+        if others.is_empty() {
+            continue;
+        }
+        let has = |configurations: &[u8], letter| {
+            configurations
+                .iter()
+                .any(|&c| letters[c as usize] == Some(letter))
+        };
+        let chosen = letters_of(n)
+            .filter(|&l| has(&chosen, l))
+            .collect::<String>();
+        let others = letters_of(n)
+            .filter(|&l| has(&others, l))
+            .collect::<String>();
+        match chosen.len() <= others.len() {
+            true => write!(f, "{}", chosen)?,
+            false => write!(f, "-{}", others)?,
+        }
+    }
+    Ok(())
+}
+
+/// Parse the number of states like `C3`, `G3` or `3`
+fn parse_states(text: &str) -> Result<u8> {
+    text.trim_start_matches(['C', 'G'])
+        .parse()
+        .map_err(|_| Error::msg(format!("invalid number of states '{}'.", text)))
+}
+
+/// Parse a range of neighbour counts like `34..58`
+fn parse_range(text: &str) -> Result<RangeInclusive<u16>> {
+    let invalid = || Error::msg(format!("invalid neighbour counts '{}'.", text));
+    let (min, max) = text.split_once("..").ok_or_else(invalid)?;
+    Ok(min.parse().map_err(|_| invalid())?..=max.parse().map_err(|_| invalid())?)
+}
+
+/// Parse a rule in the Larger than Life notation like `R5,C0,M1,S34..58,B34..45,NM`,
+/// where `N` is optional for the box shape
+fn parse_larger_than_life(text: &str) -> Result<Rule> {
+    let invalid = || Error::msg(format!("invalid rule '{}'.", text));
+    let (mut range, mut states, mut middle) = (None, None, None);
+    let (mut birth, mut survival, mut shape) = (None, None, Shape::Box);
+    for item in text.split(',') {
+        let mut chars = item.trim().chars();
+        let key = chars.next().ok_or_else(invalid)?;
+        let value = chars.as_str();
+        match key {
+            'R' => range = value.parse::<u8>().ok(),
+            'C' => states = value.parse::<u8>().ok().map(|states| states.max(2)),
+            'M' => middle = value.parse::<u8>().ok().filter(|&m| m <= 1).map(|m| m == 1),
+            'S' => survival = Some(parse_range(value)?),
+            'B' => birth = Some(parse_range(value)?),
+            'N' => {
+                shape = match value {
+                    "M" => Shape::Box,
+                    "N" => Shape::Diamond,
+                    "C" => Shape::Circle,
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    match (range, states, middle, birth, survival) {
+        (Some(range), Some(states), Some(middle), Some(birth), Some(survival)) => {
+            let neighbourhood = Neighbourhood::LargerThanLife {
+                range,
+                shape,
+                middle,
+            };
+            Rule::larger_than_life(neighbourhood, birth, survival, states)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    /// Parse a rule written like `B3/S23`, or `23/3` in the survival/birth notation.
+    ///
+    /// Neighbour counts may be followed by Hensel letters like `B2-a/S12`.
+    /// Generations rules have the number of states in the third part like `B2/S/C3` or `/2/3`,
+    /// or after the birth and survival like `B2S/C3`.
+    /// The suffix `H` or `V` chooses the hexagonal or von Neumann neighbourhood like `B2/S34H`,
+    /// and Larger than Life rules are written like `R5,C0,M1,S34..58,B34..45,NM`.
+    /// `Wireworld` is the built-in rule table, and block rules are written like `MS,D0;8;4;...`.
+    /// Stochastic rules follow a rule with `~` like `B3/S23~birth=0.9,seed=42`.
+    /// Other rule tables are read from Golly rule files starting with `@RULE`.
+    fn from_str(text: &str) -> Result<Rule> {
+        if text.trim_start().starts_with('@') {
+            return Ok(Rule::from_table(RuleTable::parse(text)?));
+        }
+        if let Some((rule, stochastic)) = text.split_once('~') {
+            return rule.parse::<Rule>()?.with_stochastic(stochastic.parse()?);
+        }
+        if text.trim().eq_ignore_ascii_case("wireworld") {
+            return Ok(Rule::wireworld());
+        }
+        let text = text.trim().to_uppercase();
+        if text.starts_with("MS,") {
+            return Ok(Rule::margolus(text.parse()?));
+        }
+        if text.starts_with('R') {
+            return parse_larger_than_life(&text);
+        }
+        let (text, neighbourhood) = match (text.strip_suffix('H'), text.strip_suffix('V')) {
+            (Some(text), _) => (text, Neighbourhood::Hexagonal),
+            (_, Some(text)) => (text, Neighbourhood::VonNeumann),
+            _ => (text.as_str(), Neighbourhood::Moore),
+        };
+        let invalid = || Error::msg(format!("invalid rule '{}'.", text));
+        let (first, second, states) = match text.split('/').collect::<Vec<_>>()[..] {
+            [first, second] if second.starts_with(['C', 'G']) => {
+                let index = first.find('S').ok_or_else(invalid)?;
+                let (birth, survival) = first.split_at(index);
+                (birth, survival, parse_states(second)?)
+            }
+            [first, second] => (first, second, 2),
+            [first, second, states] => (first, second, parse_states(states)?),
+            _ => return Err(invalid()),
+        };
+        let (birth, survival) = match (first.strip_prefix('B'), second.strip_prefix('S')) {
+            (Some(birth), Some(survival)) => (birth, survival),
+            _ => match (first.strip_prefix('S'), second.strip_prefix('B')) {
+                (Some(survival), Some(birth)) => (birth, survival),
+                _ if !text.contains(['B', 'S']) => (second, first),
+                _ => return Err(invalid()),
+            },
+        };
+        if neighbourhood != Neighbourhood::Moore
+            && [birth, survival]
+                .iter()
+                .any(|t| t.contains(char::is_alphabetic))
+        {
+            return Err(Error::msg(
+                "Hensel letters are only for the Moore neighbourhood.",
+            ));
+        }
+        let letters = hensel_letters();
+        let birth = parse_conditions(birth, &letters)?;
+        let survival = parse_conditions(survival, &letters)?;
+        if neighbourhood == Neighbourhood::Moore {
+            return Rule::with_tables(birth, survival, states);
+        }
+        let [birth, survival] = [birth, survival].map(|table| {
+            let counts = count_results(&table, u8::MAX);
+            (0..=8).filter(|&n| counts[n as usize]).collect::<Vec<_>>()
+        });
+        Rule::with_neighbourhood(&birth, &survival, states, neighbourhood)
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Written like `B3/S23`, `B2-a/S12` for non-totalistic rules, `B2/S/C3` for Generations
+    /// rules, `B2/S34H` for the hexagonal neighbourhood, or `R5,C0,M1,S34..58,B34..45,NM`
+    /// for Larger than Life neighbourhoods, followed by `~birth=0.9,seed=42` for stochastic rules
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(stochastic) = &self.stochastic {
+            let rule = Rule {
+                stochastic: None,
+                ..self.clone()
+            };
+            return write!(f, "{}~{}", rule, stochastic);
+        }
+        if let Some(table) = &self.table {
+            return write!(f, "{}", table.name());
+        }
+        if let Some(block) = &self.block {
+            return write!(f, "{}", block);
+        }
+        let suffix = match self.neighbourhood {
+            Neighbourhood::LargerThanLife {
+                range,
+                shape,
+                middle,
+            } => {
+                let states = if self.states > 2 { self.states } else { 0 };
+                let (birth, survival) = (self.birth_range, self.survival_range);
+                return write!(
+                    f,
+                    "R{},C{},M{},S{}..{},B{}..{},N{}",
+                    range, states, middle as u8, survival.0, survival.1, birth.0, birth.1, shape
+                );
+            }
+            Neighbourhood::Moore => {
+                let letters = hensel_letters();
+                write!(f, "B")?;
+                write_conditions(f, &self.birth, &letters)?;
+                write!(f, "/S")?;
+                write_conditions(f, &self.survival, &letters)?;
+                ""
+            }
+            neighbourhood => {
+                write!(f, "B")?;
+                self.birth().try_for_each(|n| write!(f, "{}", n))?;
+                write!(f, "/S")?;
+                self.survival().try_for_each(|n| write!(f, "{}", n))?;
+                match neighbourhood {
+                    Neighbourhood::Hexagonal => "H",
+                    _ => "V",
+                }
+            }
+        };
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        write!(f, "{}", suffix)
+    }
+}
+
+/// Serialized as a string like `B3/S23`, or the rule file of a rule table other than the
+/// built-in ones
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_definition())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Rule, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rule() {
+        assert_eq!("B3/S23".parse::<Rule>().unwrap(), Rule::conway());
+        assert_eq!("b3/s23".parse::<Rule>().unwrap(), Rule::conway());
+        assert_eq!("S23/B3".parse::<Rule>().unwrap(), Rule::conway());
+        assert_eq!("23/3".parse::<Rule>().unwrap(), Rule::conway());
+
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(highlife.birth().collect::<Vec<_>>(), [3, 6]);
+        assert_eq!(highlife.to_string(), "B36/S23");
+        let seeds: Rule = "B2/S".parse().unwrap();
+        assert_eq!(seeds.to_string(), "B2/S");
+
+        assert!("B9/S23".parse::<Rule>().is_err());
+        assert!("B3S23".parse::<Rule>().is_err());
+        assert!("X3/Y23".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn parse_generations_rule() {
+        let brians_brain = Rule::generations(&[2], &[], 3).unwrap();
+        assert_eq!("/2/3".parse::<Rule>().unwrap(), brians_brain);
+        assert_eq!("B2/S/C3".parse::<Rule>().unwrap(), brians_brain);
+        assert_eq!("b2s/c3".parse::<Rule>().unwrap(), brians_brain);
+        assert_eq!("S/B2/G3".parse::<Rule>().unwrap(), brians_brain);
+        assert_eq!(brians_brain.to_string(), "B2/S/C3");
+        assert_eq!(brians_brain.states(), 3);
+
+        let star_wars: Rule = "345/2/4".parse().unwrap();
+        assert_eq!(star_wars.to_string(), "B2/S345/C4");
+        assert_eq!("B2S345/C4".parse::<Rule>().unwrap(), star_wars);
+        assert_eq!("B3/S23/C2".parse::<Rule>().unwrap(), Rule::conway());
+
+        assert!("B2/S/C1".parse::<Rule>().is_err());
+        assert!("B2/S/C256".parse::<Rule>().is_err());
+        assert!("B2/S/X3".parse::<Rule>().is_err());
+        assert!("B2/C3".parse::<Rule>().is_err());
+        assert!("B2/S/C3/4".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn parse_hensel_rule() {
+        let rule: Rule = "B2-a/S12".parse().unwrap();
+        assert!(!rule.is_totalistic());
+        assert_eq!(rule.to_string(), "B2-a/S12");
+        assert_eq!(rule.birth().count(), 0);
+        assert_eq!(rule.survival().collect::<Vec<_>>(), [1, 2]);
+
+        let tlife: Rule = "b3/s2-i34q".parse().unwrap();
+        assert_eq!(tlife.to_string(), "B3/S2-i34q");
+        assert_eq!("B3/S2ceakn34q".parse::<Rule>().unwrap(), tlife);
+        // the fewer letters are written
+        let rule: Rule = "B3-aceijknq/S23".parse().unwrap();
+        assert_eq!(rule.to_string(), "B3ry/S23");
+        assert_eq!(
+            "B3aijqry/S23".parse::<Rule>().unwrap().to_string(),
+            "B3-cekn/S23"
+        );
+
+        // all letters of a count are the count itself
+        let life: Rule = "B3aceijknqry/S2aceikn3".parse().unwrap();
+        assert_eq!(life, Rule::conway());
+        assert!(life.is_totalistic());
+        assert_eq!(
+            "B2-a/S12/C3".parse::<Rule>().unwrap().to_string(),
+            "B2-a/S12/C3"
+        );
+
+        assert!("B2x/S23".parse::<Rule>().is_err());
+        assert!("B1a/S23".parse::<Rule>().is_err());
+        assert!("B3/S2-".parse::<Rule>().is_err());
+        assert!("B8c/S23".parse::<Rule>().is_err());
+        assert!("Bc/S23".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn hensel_letter_classes() {
+        let letters = hensel_letters();
+        let size = |n: u32, letter| {
+            (0..=255u8)
+                .filter(|&c| c.count_ones() == n && letters[c as usize] == Some(letter))
+                .count()
+        };
+        assert_eq!(size(1, 'c') + size(1, 'e'), 8);
+        assert_eq!((size(2, 'i'), size(2, 'k'), size(2, 'n')), (2, 8, 2));
+        assert_eq!((size(4, 'c'), size(4, 'e'), size(4, 't')), (1, 1, 4));
+        assert_eq!(size(7, 'c') + size(7, 'e'), 8);
+        assert!(letters
+            .iter()
+            .enumerate()
+            .all(|(c, l)| l.is_some() == !matches!(c, 0 | 255)));
+    }
+
+    #[test]
+    fn next_cell_by_configuration() {
+        // neighbours N and S, N and E
+        let (ns, ne) = (0b01000010, 0b00010010);
+        let rule: Rule = "B2i/S".parse().unwrap();
+        assert_eq!(rule.next_by_configuration(CELL_DEAD, ns), CELL_ALIVE);
+        assert_eq!(rule.next_by_configuration(CELL_DEAD, ne), CELL_DEAD);
+        assert_eq!(rule.next_by_configuration(CELL_ALIVE, ns), CELL_DEAD);
+
+        // adjacent neighbours NW and N, NW and W
+        let rule: Rule = "B2-a/S12".parse().unwrap();
+        assert_eq!(rule.next_by_configuration(CELL_DEAD, 0b00000011), CELL_DEAD);
+        assert_eq!(rule.next_by_configuration(CELL_DEAD, 0b00001001), CELL_DEAD);
+        assert_eq!(rule.next_by_configuration(CELL_DEAD, ns), CELL_ALIVE);
+        assert_eq!(
+            rule.next_by_configuration(CELL_ALIVE, 0b00000011),
+            CELL_ALIVE
+        );
+    }
+
+    #[test]
+    fn parse_neighbourhood_rule() {
+        let rule: Rule = "B2/S34H".parse().unwrap();
+        assert_eq!(rule.neighbourhood(), Neighbourhood::Hexagonal);
+        assert_eq!(rule.survival().collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(rule.to_string(), "B2/S34H");
+        let rule: Rule = "b1/s013v".parse().unwrap();
+        assert_eq!(rule.neighbourhood(), Neighbourhood::VonNeumann);
+        assert_eq!(rule.to_string(), "B1/S013V");
+        assert_eq!("34/2/3H".parse::<Rule>().unwrap().to_string(), "B2/S34/C3H");
+
+        assert!("B5/S23V".parse::<Rule>().is_err());
+        assert!("B2a/S34H".parse::<Rule>().is_err());
+        assert!(Rule::with_neighbourhood(&[2], &[7], 2, Neighbourhood::Hexagonal).is_err());
+    }
+
+    #[test]
+    fn parse_larger_than_life_rule() {
+        let bosco: Rule = "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap();
+        assert_eq!(
+            bosco.neighbourhood(),
+            Neighbourhood::LargerThanLife {
+                range: 5,
+                shape: Shape::Box,
+                middle: true,
+            }
+        );
+        assert_eq!(
+            bosco.birth().collect::<Vec<_>>(),
+            (34..=45).collect::<Vec<_>>()
+        );
+        assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+        assert_eq!("r5,c0,m1,s34..58,b34..45".parse::<Rule>().unwrap(), bosco);
+
+        let rule: Rule = "R2,C3,M0,S2..3,B3..3,NC".parse().unwrap();
+        assert_eq!(rule.states(), 3);
+        assert_eq!(rule.to_string(), "R2,C3,M0,S2..3,B3..3,NC");
+        assert_eq!(
+            "R3,C2,M0,S1..4,B2..2,NN"
+                .parse::<Rule>()
+                .unwrap()
+                .to_string(),
+            "R3,C0,M0,S1..4,B2..2,NN"
+        );
+
+        assert!("R11,C0,M1,S34..58,B34..45,NM".parse::<Rule>().is_err());
+        assert!("R0,C0,M1,S0..0,B1..1,NM".parse::<Rule>().is_err());
+        // 25 cells in the range 2
+        assert!("R2,C0,M1,S1..26,B1..2,NM".parse::<Rule>().is_err());
+        assert!("R2,C0,M1,S3..2,B1..2,NM".parse::<Rule>().is_err());
+        assert!("R2,C0,M2,S1..2,B1..2,NM".parse::<Rule>().is_err());
+        assert!("R2,C0,M1,S1..2,NM".parse::<Rule>().is_err());
+        assert!("R2,C0,M1,S1..2,B1..2,NX".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn next_cell_by_count() {
+        let rule: Rule = "R2,C3,M0,S2..3,B3..3,NC".parse().unwrap();
+        assert_eq!(rule.next_by_count(CELL_DEAD, 3), CELL_ALIVE);
+        assert_eq!(rule.next_by_count(CELL_DEAD, 4), CELL_DEAD);
+        assert_eq!(rule.next_by_count(CELL_ALIVE, 2), CELL_ALIVE);
+        assert_eq!(rule.next_by_count(CELL_ALIVE, 12), 2);
+        assert_eq!(rule.next_by_count(2, 3), CELL_DEAD);
+    }
+
+    #[test]
+    fn next_cell() {
+        let rule = Rule::conway();
+        assert_eq!(rule.next(CELL_DEAD, 3), CELL_ALIVE);
+        assert_eq!(rule.next(CELL_DEAD, 2), CELL_DEAD);
+        assert_eq!(rule.next(CELL_ALIVE, 2), CELL_ALIVE);
+        assert_eq!(rule.next(CELL_ALIVE, 4), CELL_DEAD);
+        // out of the Moore neighbourhood
+        let all: Rule = "B012345678/S012345678".parse().unwrap();
+        assert_eq!(all.next(CELL_ALIVE, 8), CELL_ALIVE);
+        for count in [9, 15, 16, u8::MAX] {
+            assert_eq!(all.next(CELL_ALIVE, count), CELL_DEAD);
+        }
+    }
+
+    #[test]
+    fn next_generations_cell() {
+        let star_wars: Rule = "345/2/4".parse().unwrap();
+        assert_eq!(star_wars.next(CELL_DEAD, 2), CELL_ALIVE);
+        assert_eq!(star_wars.next(CELL_ALIVE, 3), CELL_ALIVE);
+        assert_eq!(star_wars.next(CELL_ALIVE, 2), 2);
+        // dying cells decay whatever the neighbours are
+        assert_eq!(star_wars.next(2, 2), 3);
+        assert_eq!(star_wars.next(2, 4), 3);
+        assert_eq!(star_wars.next(3, 2), CELL_DEAD);
+        assert_eq!(star_wars.next(200, 2), CELL_DEAD);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rule() {
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(serde_json::to_string(&highlife).unwrap(), "\"B36/S23\"");
+        assert_eq!(serde_json::from_str::<Rule>("\"23/36\"").unwrap(), highlife);
+        assert!(serde_json::from_str::<Rule>("\"B9/S\"").is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! Compact binary snapshot of a world for checkpointing and resuming long runs.
+//!
+//! All integers are little-endian:
+//!
+//! | bytes | content |
+//! |-------|---------|
+//! | 6 | magic `LGSNAP` |
+//! | 2 | format version, [`SNAPSHOT_VERSION`] |
+//! | 1 | bits per cell, 1 for life-like rules and 2, 4 or 8 for Generations rules |
+//! | 8 | `nx` including the boundary |
+//! | 8 | `ny` including the boundary |
+//! | 8 | generation |
+//! | 4 + n | length and UTF-8 of the rule like `B3/S23`, or the rule file of a rule table |
+//! | ⌈(nx - 2)(ny - 2) × bits / 8⌉ | states of the inner cells row by row, the lowest bits first |
+//!
+//! Ages are not stored, and restart from the snapshot.
+
+use std::io::{Read, Write};
+
+use anyhow::{Error, Result};
+
+use crate::{Cell, Rule, World, CELL_DEAD};
+
+/// Magic bytes at the start of a snapshot
+pub const SNAPSHOT_MAGIC: &[u8; 6] = b"LGSNAP";
+/// Version of the snapshot format
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+impl World {
+    /// Write the snapshot of the present generation
+    pub fn write_snapshot(&self, mut writer: impl Write) -> Result<()> {
+        let rule = self.rule.to_definition();
+        writer.write_all(SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+        writer.write_all(&[bits_per_cell(&self.rule)])?;
+        for n in [self.nx, self.ny, self.generation] {
+            writer.write_all(&(n as u64).to_le_bytes())?;
+        }
+        writer.write_all(&(rule.len() as u32).to_le_bytes())?;
+        writer.write_all(rule.as_bytes())?;
+        writer.write_all(&self.packed_cells())?;
+        Ok(())
+    }
+
+    /// Read a world written by [`World::write_snapshot`]
+    pub fn read_snapshot(mut reader: impl Read) -> Result<World> {
+        let mut magic = [0; SNAPSHOT_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(Error::msg("not a snapshot."));
+        }
+        let version = u16::from_le_bytes(read_array(&mut reader)?);
+        let [bits_per_cell] = read_array(&mut reader)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(Error::msg(format!(
+                "unsupported snapshot version {}.",
+                version
+            )));
+        }
+        let nx = to_usize(u64::from_le_bytes(read_array(&mut reader)?))?;
+        let ny = to_usize(u64::from_le_bytes(read_array(&mut reader)?))?;
+        let generation = to_usize(u64::from_le_bytes(read_array(&mut reader)?))?;
+        let rule_len = u32::from_le_bytes(read_array(&mut reader)?);
+        let rule = read_vec(&mut reader, rule_len as u64, "rule")?;
+        let rule = String::from_utf8(rule)?.parse()?;
+        if bits_per_cell != self::bits_per_cell(&rule) {
+            return Err(Error::msg(format!(
+                "invalid bits per cell {}.",
+                bits_per_cell
+            )));
+        }
+        let cells_len = packed_len(nx, ny, bits_per_cell)?;
+        let cells = read_vec(&mut reader, cells_len as u64, "cells")?;
+        World::from_packed_cells(nx, ny, generation, rule, &cells)
+    }
+
+    /// Snapshot of the present generation as bytes
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_snapshot(&mut bytes)
+            .expect("writing to a vector never fails");
+        bytes
+    }
+
+    /// Read a world from the bytes of a snapshot, which must not have trailing bytes
+    pub fn from_snapshot(mut bytes: &[u8]) -> Result<World> {
+        let world = World::read_snapshot(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(Error::msg("trailing bytes after the snapshot."));
+        }
+        Ok(world)
+    }
+
+    /// States of the inner cells row by row, packed with the lowest bits first
+    fn packed_cells(&self) -> Vec<u8> {
+        let bits = bits_per_cell(&self.rule) as usize;
+        let mut packed =
+            vec![0; packed_len(self.nx, self.ny, bits as u8).expect("invalid world size")];
+        let inner = (1..self.ny - 1).flat_map(|iy| (1..self.nx - 1).map(move |ix| (ix, iy)));
+        for (i, (ix, iy)) in inner.enumerate() {
+            let cell = self.get_present_cell(ix, iy).min(self.rule.states() - 1);
+            packed[i * bits / 8] |= cell << (i * bits % 8);
+        }
+        packed
+    }
+
+    /// Create a world of the inner cells packed by [`World::packed_cells`]
+    fn from_packed_cells(
+        nx: usize,
+        ny: usize,
+        generation: usize,
+        rule: Rule,
+        packed: &[u8],
+    ) -> Result<World> {
+        let bits = bits_per_cell(&rule) as usize;
+        if packed.len() != packed_len(nx, ny, bits as u8)? {
+            return Err(Error::msg("invalid cell size."));
+        }
+        let mask = ((1u16 << bits) - 1) as u8;
+        let states = rule.states();
+        let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny])?.with_rule(rule);
+        let inner = (1..ny - 1).flat_map(|iy| (1..nx - 1).map(move |ix| (ix, iy)));
+        for (i, (ix, iy)) in inner.enumerate() {
+            let cell: Cell = packed[i * bits / 8] >> (i * bits % 8) & mask;
+            if cell >= states {
+                return Err(Error::msg(format!("invalid cell state {}.", cell)));
+            }
+            world.set_present_cell(ix, iy, cell);
+        }
+        world.generation = generation;
+        Ok(world)
+    }
+}
+
+/// Bits per cell holding all states of the rule, which divide a byte
+fn bits_per_cell(rule: &Rule) -> u8 {
+    match rule.states() {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    }
+}
+
+/// Number of bytes of the packed inner cells, checking the size includes the boundary
+fn packed_len(nx: usize, ny: usize, bits_per_cell: u8) -> Result<usize> {
+    if nx < 3 || ny < 3 {
+        return Err(Error::msg("invalid world size."));
+    }
+    let bits = (nx - 2)
+        .checked_mul(ny - 2)
+        .and_then(|cells| cells.checked_mul(bits_per_cell as usize))
+        .ok_or_else(|| Error::msg("invalid world size."))?;
+    Ok(bits.div_ceil(8))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Read `len` bytes, allocating as many as are actually read, so that a broken length in the
+/// header does not allocate beyond the snapshot
+fn read_vec(reader: &mut impl Read, len: u64, name: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(Error::msg(format!("truncated {}.", name)));
+    }
+    Ok(bytes)
+}
+
+fn to_usize(n: u64) -> Result<usize> {
+    usize::try_from(n).map_err(|_| Error::msg(format!("too large number {}.", n)))
+}
+
+/// Serialized form of a world, with the inner cells packed like the snapshot
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "World")]
+struct SerdeWorld {
+    nx: usize,
+    ny: usize,
+    generation: usize,
+    rule: Rule,
+    cells: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for World {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerdeWorld {
+            nx: self.nx,
+            ny: self.ny,
+            generation: self.generation,
+            rule: self.rule.clone(),
+            cells: self.packed_cells(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for World {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<World, D::Error> {
+        let world = SerdeWorld::deserialize(deserializer)?;
+        World::from_packed_cells(
+            world.nx,
+            world.ny,
+            world.generation,
+            world.rule,
+            &world.cells,
+        )
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pattern, RuleTable};
+
+    fn glider_world() -> Result<World> {
+        let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!")?;
+        let mut world = World::new(13, 11, &[CELL_DEAD; 13 * 11])?.with_rule("B36/S23".parse()?);
+        world.paste(&glider, 2, 1);
+        Ok(world)
+    }
+
+    fn assert_same_world(a: &World, b: &World) {
+        assert_eq!((a.nx(), a.ny()), (b.nx(), b.ny()));
+        assert_eq!(a.generation(), b.generation());
+        assert_eq!(a.rule(), b.rule());
+        assert_eq!(a.present_cells(), b.present_cells());
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let mut world = glider_world()?;
+        for _ in 0..5 {
+            world.next();
+        }
+        let snapshot = world.to_snapshot();
+        // header, 7 bytes of the rule and 99 inner cells
+        assert_eq!(snapshot.len(), 6 + 2 + 1 + 24 + 4 + 7 + 13);
+
+        let mut resumed = World::from_snapshot(&snapshot)?;
+        assert_same_world(&world, &resumed);
+        for _ in 0..20 {
+            world.next();
+            resumed.next();
+        }
+        assert_same_world(&world, &resumed);
+        assert_eq!(resumed.generation(), 25);
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_snapshot() -> Result<()> {
+        let table = "@RULE Turn\n@TABLE\nn_states:3\nneighborhood:vonNeumann\n\
+                     symmetries:none\n0,1,0,0,0,2\n2,0,0,0,0,1\n";
+        let rule = Rule::from_table(RuleTable::parse(table)?);
+        let mut world = World::new(6, 6, &[CELL_DEAD; 36])?.with_rule(rule);
+        world.set_present_cell(2, 2, 1);
+        world.next();
+        let resumed = World::from_snapshot(&world.to_snapshot())?;
+        assert_same_world(&world, &resumed);
+        assert_eq!(resumed.rule().table().map(RuleTable::source), Some(table));
+
+        let wireworld = world.with_rule(Rule::wireworld()).to_snapshot();
+        assert_eq!(wireworld.len(), 6 + 2 + 1 + 24 + 4 + 9 + 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_snapshot() -> Result<()> {
+        let snapshot = glider_world()?.to_snapshot();
+        assert!(World::from_snapshot(&snapshot[..snapshot.len() - 1]).is_err());
+        assert!(World::from_snapshot(&[&snapshot[..], &[0]].concat()).is_err());
+        assert!(World::from_snapshot(b"LGSNAX").is_err());
+
+        let mut version = snapshot.clone();
+        version[6] = 2;
+        assert!(World::from_snapshot(&version).is_err());
+
+        // nx = 2
+        let mut size = snapshot.clone();
+        size[9] = 2;
+        assert!(World::from_snapshot(&size).is_err());
+
+        // nx and ny of 2^20, whose 128 GiB of cells are missing
+        let mut huge = snapshot[..snapshot.len() - 13].to_vec();
+        huge[9..17].copy_from_slice(&(1u64 << 20).to_le_bytes());
+        huge[17..25].copy_from_slice(&(1u64 << 20).to_le_bytes());
+        assert!(World::from_snapshot(&huge).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_generations_snapshot() -> Result<()> {
+        let mut world = glider_world()?.with_rule("345/2/4".parse()?);
+        for _ in 0..3 {
+            world.next();
+        }
+        assert!(world.present_cells().contains(&3));
+        let snapshot = world.to_snapshot();
+        assert_eq!(snapshot[8], 2);
+        assert_eq!(snapshot.len(), 6 + 2 + 1 + 24 + 4 + 10 + 25);
+        assert_same_world(&world, &World::from_snapshot(&snapshot)?);
+
+        // state 3 of 3 states
+        let mut invalid = world.with_rule("/2/3".parse()?).to_snapshot();
+        let index = invalid.len() - 25 + 4;
+        invalid[index] = 0b11;
+        assert!(World::from_snapshot(&invalid).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_world() -> Result<()> {
+        let mut world = glider_world()?;
+        world.next();
+        let json = serde_json::to_string(&world)?;
+        assert!(json.contains("\"generation\":1"));
+        assert!(json.contains("\"rule\":\"B36/S23\""));
+        assert_same_world(&world, &serde_json::from_str(&json)?);
+
+        let table = "@RULE Turn\n@TABLE\nn_states:2\nneighborhood:vonNeumann\n0,1,0,0,0,1\n";
+        let world = world.with_rule(Rule::from_table(RuleTable::parse(table)?));
+        let json = serde_json::to_string(&world)?;
+        assert!(json.contains("@RULE Turn"));
+        assert_same_world(&world, &serde_json::from_str(&json)?);
+
+        let broken = json.replace("\"nx\":13", "\"nx\":14");
+        assert!(serde_json::from_str::<World>(&broken).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+//! Stochastic rules, where births and survivals happen with probabilities and cells flip at
+//! random by the temperature.
+//!
+//! The random numbers of a generation depend only on the seed and the generation, so that a run
+//! is reproducible, and a world restored from a snapshot steps the same as the original one.
+//! They are drawn from ChaCha8, whose stream is the same on every platform and release.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Rule, World, CELL_ALIVE, CELL_DEAD};
+
+/// Probabilities of births, survivals and flips of a stochastic rule.
+///
+/// Written after the rule like `B3/S23~birth=0.9,survival=0.95,temperature=0.001,seed=42`,
+/// where omitted probabilities are 1 for births and survivals and 0 for the temperature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stochastic {
+    /// probability that a dead cell is born when the rule gives birth to it
+    pub birth: f64,
+    /// probability that an alive cell survives when the rule keeps it alive
+    pub survival: f64,
+    /// probability that a dead or alive cell flips to the other after every generation, while
+    /// dying cells of Generations rules keep decaying
+    pub temperature: f64,
+    /// seed of the random numbers
+    pub seed: u64,
+}
+
+impl Default for Stochastic {
+    fn default() -> Self {
+        Stochastic {
+            birth: 1.0,
+            survival: 1.0,
+            temperature: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+// probabilities are checked to be in [0, 1], which excludes NaN, and -0 is normalized
+impl Eq for Stochastic {}
+
+impl Hash for Stochastic {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for probability in [self.birth, self.survival, self.temperature] {
+            probability.to_bits().hash(state);
+        }
+        self.seed.hash(state);
+    }
+}
+
+impl Stochastic {
+    /// Check the probabilities are in [0, 1]
+    fn checked(self) -> Result<Stochastic> {
+        let mut checked = self;
+        for probability in [
+            &mut checked.birth,
+            &mut checked.survival,
+            &mut checked.temperature,
+        ] {
+            if !(0.0..=1.0).contains(probability) {
+                return Err(Error::msg(format!("invalid probability {}.", probability)));
+            }
+            *probability += 0.0;
+        }
+        Ok(checked)
+    }
+}
+
+impl FromStr for Stochastic {
+    type Err = Error;
+
+    /// Parse probabilities written like `birth=0.9,survival=0.95,temperature=0.001,seed=42`
+    fn from_str(text: &str) -> Result<Stochastic> {
+        let mut stochastic = Stochastic::default();
+        for item in text.split(',') {
+            let invalid = || Error::msg(format!("invalid stochastic parameter '{}'.", item));
+            let (key, value) = item.split_once('=').ok_or_else(invalid)?;
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "birth" => stochastic.birth = value.parse().map_err(|_| invalid())?,
+                "survival" => stochastic.survival = value.parse().map_err(|_| invalid())?,
+                "temperature" => stochastic.temperature = value.parse().map_err(|_| invalid())?,
+                "seed" => stochastic.seed = value.parse().map_err(|_| invalid())?,
+                _ => return Err(invalid()),
+            }
+        }
+        stochastic.checked()
+    }
+}
+
+impl fmt::Display for Stochastic {
+    /// Written with the probabilities other than the defaults and the seed
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let default = Stochastic::default();
+        let probabilities = [
+            ("birth", self.birth, default.birth),
+            ("survival", self.survival, default.survival),
+            ("temperature", self.temperature, default.temperature),
+        ];
+        for (key, probability, default) in probabilities {
+            if probability != default {
+                write!(f, "{}={},", key, probability)?;
+            }
+        }
+        write!(f, "seed={}", self.seed)
+    }
+}
+
+impl Rule {
+    /// The rule with births and survivals happening with probabilities, and with cells flipping
+    /// by the temperature. Rule tables and block rules are not stochastic.
+    pub fn with_stochastic(self, stochastic: Stochastic) -> Result<Rule> {
+        if self.table().is_some() || self.block().is_some() {
+            return Err(Error::msg(
+                "only rules of births and survivals are stochastic.",
+            ));
+        }
+        Ok(self.with_checked_stochastic(stochastic.checked()?))
+    }
+}
+
+impl World {
+    /// Apply the probabilities to the cells stepped by the rule from the previous cells,
+    /// and count the ages, which the rule leaves to the noise
+    pub(crate) fn apply_stochastic(&mut self, stochastic: &Stochastic) {
+        let generation = self.generation as u64 - 1;
+        let mut rng = ChaCha8Rng::seed_from_u64(
+            stochastic.seed ^ generation.wrapping_mul(0x9e3779b97f4a7c15),
+        );
+        let death = match self.rule.states() {
+            2 => CELL_DEAD,
+            _ => 2,
+        };
+        let previous = 1 - self.present;
+        for iy in 1..(self.ny - 1) {
+            for ix in 1..(self.nx - 1) {
+                let previous_cell = self.get_cell(previous, ix, iy);
+                let mut cell = self.get_cell(self.present, ix, iy);
+                match (previous_cell, cell) {
+                    (CELL_DEAD, CELL_ALIVE) if !rng.gen_bool(stochastic.birth) => cell = CELL_DEAD,
+                    (CELL_ALIVE, CELL_ALIVE) if !rng.gen_bool(stochastic.survival) => cell = death,
+                    _ => {}
+                }
+                if rng.gen_bool(stochastic.temperature) {
+                    cell = match cell {
+                        CELL_DEAD => CELL_ALIVE,
+                        CELL_ALIVE => CELL_DEAD,
+                        dying => dying,
+                    };
+                }
+                self.update_cell(self.present, ix, iy, cell);
+                self.update_age(ix, iy, cell == previous_cell);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soup;
+
+    fn soup_world(rule: &str) -> Result<World> {
+        let mut world = World::new(32, 32, &[CELL_DEAD; 32 * 32])?.with_rule(rule.parse()?);
+        world.paste(&soup(24, 24, 11), 4, 4);
+        Ok(world)
+    }
+
+    #[test]
+    fn test_parse_stochastic_rule() -> Result<()> {
+        let rule: Rule = "B3/S23~birth=0.9,survival=0.95,temperature=0.001,seed=42".parse()?;
+        let stochastic = rule.stochastic().unwrap();
+        assert_eq!(stochastic.birth, 0.9);
+        assert_eq!(stochastic.temperature, 0.001);
+        assert_eq!(stochastic.seed, 42);
+        assert_eq!(
+            rule.to_string(),
+            "B3/S23~birth=0.9,survival=0.95,temperature=0.001,seed=42"
+        );
+        let rule: Rule = "b2/s/c3 ~ Survival=0.5".parse()?;
+        assert_eq!(rule.states(), 3);
+        assert_eq!(rule.to_string(), "B2/S/C3~survival=0.5,seed=0");
+        let ltl = "R2,C0,M1,S2..5,B3..4,NM~birth=0.5,seed=1";
+        assert_eq!(ltl.parse::<Rule>()?.to_string(), ltl);
+
+        assert!("B3/S23~birth=1.5".parse::<Rule>().is_err());
+        assert!("B3/S23~birth=-0.1".parse::<Rule>().is_err());
+        assert!("B3/S23~birth=NaN".parse::<Rule>().is_err());
+        assert!("B3/S23~pressure=0.1".parse::<Rule>().is_err());
+        assert!("B3/S23~".parse::<Rule>().is_err());
+        assert!("Wireworld~birth=0.5".parse::<Rule>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stochastic_rule() -> Result<()> {
+        // without noise, the same as the rule
+        let mut life = soup_world("B3/S23")?;
+        let mut noiseless = soup_world("B3/S23~seed=5")?;
+        for _ in 0..8 {
+            life.next();
+            noiseless.next();
+        }
+        assert_eq!(life.present_cells(), noiseless.present_cells());
+
+        // reproducible by the seed
+        let noisy = "B3/S23~birth=0.8,survival=0.9,temperature=0.01,seed=7";
+        let (mut a, mut b) = (soup_world(noisy)?, soup_world(noisy)?);
+        let mut c = soup_world(&noisy.replace("seed=7", "seed=8"))?;
+        for _ in 0..8 {
+            a.next();
+            b.next();
+            c.next();
+        }
+        assert_eq!(a.present_cells(), b.present_cells());
+        assert_ne!(a.present_cells(), c.present_cells());
+        assert_ne!(a.present_cells(), life.present_cells());
+
+        // and by the generation of a restored snapshot
+        let mut restored = World::from_snapshot(&a.to_snapshot())?;
+        for _ in 0..8 {
+            a.next();
+            restored.next();
+        }
+        assert_eq!(a.present_cells(), restored.present_cells());
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_stream() -> Result<()> {
+        // pinned, so that runs stay reproducible across platforms and releases
+        let rule = "B3/S23~temperature=0.5,seed=42".parse()?;
+        let mut world = World::new(8, 6, &[CELL_DEAD; 48])?.with_rule(rule);
+        world.next();
+        assert_eq!(
+            world.alive_cells().collect::<Vec<_>>(),
+            [
+                (3, 1),
+                (5, 1),
+                (6, 1),
+                (1, 2),
+                (4, 2),
+                (4, 3),
+                (6, 3),
+                (1, 4),
+                (2, 4),
+                (3, 4),
+                (4, 4),
+                (6, 4)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_probabilities() -> Result<()> {
+        // no births
+        let mut world = soup_world("B3/S23~birth=0")?;
+        let initial = world.population();
+        for _ in 0..4 {
+            let previous = world.clone();
+            world.next();
+            assert!(world
+                .alive_cells()
+                .all(|(ix, iy)| previous.get_present_cell(ix, iy) == CELL_ALIVE));
+        }
+        assert!(world.population() < initial);
+
+        // the same cells and ages as the rule without births
+        let mut world = soup_world("B3/S23~birth=0")?;
+        let mut no_births = soup_world("B/S23")?;
+        for _ in 0..4 {
+            world.next();
+            no_births.next();
+        }
+        assert_eq!(world.present_cells(), no_births.present_cells());
+        let ages = |world: &World| {
+            (0..32 * 32)
+                .map(|i| world.get_present_age(i % 32, i / 32))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ages(&world), ages(&no_births));
+
+        // every cell flips
+        let mut world =
+            World::new(6, 5, &[CELL_DEAD; 30])?.with_rule("B3/S23~temperature=1".parse()?);
+        world.next();
+        assert_eq!(world.population(), 4 * 3);
+        assert_eq!(world.get_present_age(1, 1), 0);
+
+        // but dying cells do not flip
+        let mut world =
+            World::new(6, 6, &[CELL_DEAD; 36])?.with_rule("B/S/C3~temperature=1".parse()?);
+        world.set_present_cell(2, 2, CELL_ALIVE);
+        world.next();
+        assert_eq!(world.get_present_cell(2, 2), 2);
+        assert_eq!(world.population(), 4 * 4 - 1);
+
+        // alive cells not surviving start dying in Generations rules
+        let mut world = soup_world("B/S012345678/C3~survival=0")?;
+        world.next();
+        assert_eq!(world.population(), 0);
+        assert!(world.present_cells().contains(&2));
+        Ok(())
+    }
+}
@@ -0,0 +1,682 @@
+//! Multi-state rules of Golly rule files with a `@TABLE` section, like Wireworld.
+//!
+//! A table has the number of states, the neighbourhood, the symmetries and the transitions:
+//!
+//! ```text
+//! @RULE Name
+//! @TABLE
+//! n_states:4
+//! neighborhood:Moore
+//! symmetries:permute
+//! var a={0,1,2,3}
+//! 1,a,b,c,d,e,f,g,h,2
+//! ```
+//!
+//! A transition lists the state of the cell, the states of the neighbours clockwise from N, and
+//! the next state. Every occurrence of a variable in a transition takes the same state, and the
+//! first matching transition decides the next state; cells without one keep their states.
+//! Transitions are compiled into bitmasks of the transitions matching each state at each
+//! position, so that a cell finds its transition by a few AND operations.
+//!
+//! See <https://golly.sourceforge.io/Help/formats.html#rule>
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{Error, Result};
+
+use crate::neighbourhood::Neighbourhood;
+use crate::{Cell, Rule, World};
+
+/// Wireworld of 4 states: 0 empty, 1 electron head, 2 electron tail and 3 conductor
+pub const WIREWORLD: &str = "\
+@RULE Wireworld
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+var o={0,2,3}
+var p={o}
+var q={o}
+var r={o}
+var s={o}
+var t={o}
+var u={o}
+# electron heads become tails, and tails become conductors
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+# conductors become heads with 1 or 2 heads around
+3,1,o,p,q,r,s,t,u,1
+3,1,1,o,p,q,r,s,t,1
+";
+
+/// Compiled rule of a Golly rule table.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RuleTable {
+    name: String,
+    /// rule file the table is parsed from, written in snapshots and serialized rules
+    source: String,
+    states: u8,
+    neighbourhood: Neighbourhood,
+    /// number of 64-bit words of a mask
+    words: usize,
+    /// `masks[(position * states + state) * words + w]`: bits of the transitions matching
+    /// the state at the position, the cell itself at the position 0
+    masks: Vec<u64>,
+    /// next state of every transition in the order of the table
+    outputs: Vec<Cell>,
+}
+
+/// Set of states as bits
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct States([u64; 4]);
+
+impl States {
+    fn single(state: Cell) -> States {
+        let mut states = States::default();
+        states.0[state as usize / 64] |= 1 << (state % 64);
+        states
+    }
+
+    fn contains(&self, state: Cell) -> bool {
+        self.0[state as usize / 64] >> (state % 64) & 1 == 1
+    }
+
+    fn union(&self, other: &States) -> States {
+        States([0, 1, 2, 3].map(|i| self.0[i] | other.0[i]))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Cell> + '_ {
+        (0..=Cell::MAX).filter(|&state| self.contains(state))
+    }
+}
+
+/// Item of a transition
+#[derive(Clone, Debug)]
+enum Entry {
+    States(States),
+    Variable(String),
+}
+
+impl RuleTable {
+    /// Parse a Golly rule file, ignoring sections other than `@RULE` and `@TABLE`
+    pub fn parse(text: &str) -> Result<RuleTable> {
+        let mut name = None;
+        let mut section = "";
+        let mut states = None;
+        let mut neighbourhood = Neighbourhood::Moore;
+        let mut symmetries = "none".to_string();
+        let mut variables = HashMap::new();
+        let mut transitions = Vec::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('@') {
+                let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+                section = key;
+                if key == "RULE" {
+                    name = Some(value.trim().to_string());
+                }
+                continue;
+            }
+            if section != "TABLE" {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim();
+                match key.trim() {
+                    "n_states" => states = Some(parse_states(value)?),
+                    "neighborhood" => neighbourhood = parse_neighbourhood(value)?,
+                    "symmetries" => symmetries = value.to_string(),
+                    key => return Err(Error::msg(format!("unknown table key '{}'.", key))),
+                }
+            } else if let Some(var) = line.strip_prefix("var ") {
+                let states = states.ok_or(Error::msg("missing n_states before variables."))?;
+                let (key, value) = var
+                    .split_once('=')
+                    .ok_or(Error::msg(format!("invalid variable '{}'.", line)))?;
+                let value = parse_set(value.trim(), states, &variables)?;
+                variables.insert(key.trim().to_string(), value);
+            } else {
+                let states = states.ok_or(Error::msg("missing n_states before transitions."))?;
+                transitions.push(parse_transition(line, states, &variables)?);
+            }
+        }
+        let name = name.filter(|name| !name.is_empty());
+        let name = name.ok_or(Error::msg("missing @RULE name."))?;
+        // the name is written in RLE headers, and must not read back as another rule
+        if text != WIREWORLD && name.parse::<Rule>().is_ok() {
+            return Err(Error::msg(format!(
+                "the rule name '{}' is of another rule.",
+                name
+            )));
+        }
+        let states = states.ok_or(Error::msg("missing @TABLE with n_states."))?;
+
+        let neighbours = neighbourhood.size() as usize;
+        let symmetries = symmetry_permutations(&symmetries, neighbours)?;
+        let mut expanded = Vec::new();
+        for transition in transitions {
+            if transition.len() != neighbours + 2 {
+                return Err(Error::msg(format!(
+                    "transition of {} items for {} neighbours.",
+                    transition.len(),
+                    neighbours
+                )));
+            }
+            for (inputs, output) in bind_variables(&transition, &variables)? {
+                let mut seen = HashSet::new();
+                for symmetric in apply_symmetries(&inputs, &symmetries) {
+                    if seen.insert(symmetric.clone()) {
+                        expanded.push((symmetric, output));
+                    }
+                }
+            }
+        }
+        Ok(RuleTable::compile(
+            name,
+            text,
+            states,
+            neighbourhood,
+            &expanded,
+        ))
+    }
+
+    /// Compile the transitions of the states at all positions into bitmasks
+    fn compile(
+        name: String,
+        source: &str,
+        states: u8,
+        neighbourhood: Neighbourhood,
+        transitions: &[(Vec<States>, Cell)],
+    ) -> RuleTable {
+        let positions = neighbourhood.size() as usize + 1;
+        let words = transitions.len().div_ceil(64).max(1);
+        let mut masks = vec![0; positions * states as usize * words];
+        for (i, (inputs, _)) in transitions.iter().enumerate() {
+            for (position, input) in inputs.iter().enumerate() {
+                for state in input.iter() {
+                    let index = (position * states as usize + state as usize) * words + i / 64;
+                    masks[index] |= 1 << (i % 64);
+                }
+            }
+        }
+        RuleTable {
+            name,
+            source: source.to_string(),
+            states,
+            neighbourhood,
+            words,
+            masks,
+            outputs: transitions.iter().map(|&(_, output)| output).collect(),
+        }
+    }
+
+    /// Name of the rule in `@RULE`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The rule file the table is parsed from
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Number of states including the state 0
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Moore, von Neumann or hexagonal neighbourhood
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// The next state of the cell with the states of the cell and its neighbours clockwise
+    /// from N, where states out of the table are read as 0
+    pub fn next(&self, cells: &[Cell]) -> Cell {
+        for w in 0..self.words {
+            let mut matching = u64::MAX;
+            for (position, &cell) in cells.iter().enumerate() {
+                let state = match cell < self.states {
+                    true => cell as usize,
+                    false => 0,
+                };
+                matching &= self.masks[(position * self.states as usize + state) * self.words + w];
+                if matching == 0 {
+                    break;
+                }
+            }
+            if matching != 0 {
+                return self.outputs[w * 64 + matching.trailing_zeros() as usize];
+            }
+        }
+        cells[0]
+    }
+}
+
+impl Rule {
+    /// Create a rule of a rule table
+    pub fn from_table(table: RuleTable) -> Rule {
+        Rule::with_table(Arc::new(table))
+    }
+
+    /// Wireworld, the built-in rule table of [`WIREWORLD`]
+    pub fn wireworld() -> Rule {
+        static TABLE: OnceLock<Arc<RuleTable>> = OnceLock::new();
+        let table = TABLE.get_or_init(|| {
+            Arc::new(RuleTable::parse(WIREWORLD).expect("invalid built-in rule table"))
+        });
+        Rule::with_table(Arc::clone(table))
+    }
+}
+
+impl World {
+    /// Step the world by a rule table
+    pub(crate) fn next_by_table(&mut self, table: &RuleTable) {
+        let offsets = neighbour_offsets(table.neighbourhood);
+        let next = 1 - self.present;
+        let mut cells = Vec::with_capacity(offsets.len() + 1);
+        for iy in 1..(self.ny - 1) {
+            for ix in 1..(self.nx - 1) {
+                cells.clear();
+                cells.push(self.get_cell(self.present, ix, iy));
+                for &(dx, dy) in offsets {
+                    let (x, y) = (ix.wrapping_add_signed(dx), iy.wrapping_add_signed(dy));
+                    cells.push(self.get_cell(self.present, x, y));
+                }
+                let next_cell = table.next(&cells);
+                self.update_cell(next, ix, iy, next_cell);
+                self.update_age(ix, iy, cells[0] == next_cell);
+            }
+        }
+        self.generation += 1;
+        self.present = next;
+    }
+}
+
+/// Offsets of the neighbours clockwise from N in the order of rule tables
+fn neighbour_offsets(neighbourhood: Neighbourhood) -> &'static [(isize, isize)] {
+    match neighbourhood {
+        Neighbourhood::VonNeumann => &[(0, -1), (1, 0), (0, 1), (-1, 0)],
+        Neighbourhood::Hexagonal => &[(0, -1), (1, 0), (1, 1), (0, 1), (-1, 0), (-1, -1)],
+        _ => &[
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ],
+    }
+}
+
+fn parse_states(text: &str) -> Result<u8> {
+    match text.parse::<u8>() {
+        Ok(states) if states >= 2 => Ok(states),
+        _ => Err(Error::msg(format!("invalid n_states '{}'.", text))),
+    }
+}
+
+fn parse_neighbourhood(text: &str) -> Result<Neighbourhood> {
+    match text {
+        "Moore" => Ok(Neighbourhood::Moore),
+        "vonNeumann" => Ok(Neighbourhood::VonNeumann),
+        "hexagonal" => Ok(Neighbourhood::Hexagonal),
+        _ => Err(Error::msg(format!("unsupported neighborhood '{}'.", text))),
+    }
+}
+
+fn parse_state(text: &str, states: u8) -> Result<Cell> {
+    match text.parse::<Cell>() {
+        Ok(state) if state < states => Ok(state),
+        _ => Err(Error::msg(format!("invalid state '{}'.", text))),
+    }
+}
+
+/// Parse a set of states and variables like `{0,1,a}`
+fn parse_set(text: &str, states: u8, variables: &HashMap<String, States>) -> Result<States> {
+    let items = text
+        .strip_prefix('{')
+        .and_then(|text| text.strip_suffix('}'))
+        .ok_or(Error::msg(format!("invalid set '{}'.", text)))?;
+    let mut set = States::default();
+    for item in items.split(',').map(str::trim) {
+        set = set.union(&match variables.get(item) {
+            Some(variable) => *variable,
+            None => States::single(parse_state(item, states)?),
+        });
+    }
+    Ok(set)
+}
+
+/// Parse a transition of states, variables and sets separated by commas, or of single digit
+/// states without commas
+fn parse_transition(
+    line: &str,
+    states: u8,
+    variables: &HashMap<String, States>,
+) -> Result<Vec<Entry>> {
+    let mut items = Vec::new();
+    if !line.contains(',') {
+        for c in line.chars().filter(|c| !c.is_whitespace()) {
+            items.push(c.to_string());
+        }
+    } else {
+        // commas in sets do not separate items
+        let mut item = String::new();
+        for c in line.chars() {
+            match c {
+                ',' if !item.starts_with('{') || item.ends_with('}') => {
+                    items.push(std::mem::take(&mut item));
+                }
+                c => item.push(c),
+            }
+        }
+        items.push(item);
+    }
+    items
+        .iter()
+        .map(|item| {
+            let item = item.trim();
+            if item.starts_with('{') {
+                Ok(Entry::States(parse_set(item, states, variables)?))
+            } else if variables.contains_key(item) {
+                Ok(Entry::Variable(item.to_string()))
+            } else {
+                Ok(Entry::States(States::single(parse_state(item, states)?)))
+            }
+        })
+        .collect()
+}
+
+/// Expand the variables occurring more than once into every combination of their states,
+/// giving the sets of the cell and its neighbours and the next state
+fn bind_variables(
+    transition: &[Entry],
+    variables: &HashMap<String, States>,
+) -> Result<Vec<(Vec<States>, Cell)>> {
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    for entry in transition {
+        if let Entry::Variable(name) = entry {
+            *occurrences.entry(name).or_default() += 1;
+        }
+    }
+    let mut bound = occurrences
+        .iter()
+        .filter(|&(_, &n)| n > 1)
+        .map(|(&name, _)| name)
+        .collect::<Vec<_>>();
+    bound.sort();
+    let (output, inputs) = transition.split_last().expect("empty transition");
+    match output {
+        Entry::States(states) if states.iter().count() != 1 => {
+            return Err(Error::msg("a set in output."));
+        }
+        Entry::Variable(name) if !bound.contains(&name.as_str()) => {
+            return Err(Error::msg(format!(
+                "unbound variable '{}' in output.",
+                name
+            )));
+        }
+        _ => {}
+    }
+
+    let mut expanded = Vec::new();
+    let mut assignment = vec![0; bound.len()];
+    let values = bound
+        .iter()
+        .map(|&name| variables[name].iter().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    if values.iter().any(Vec::is_empty) {
+        return Ok(expanded);
+    }
+    loop {
+        let value = |name: &str| {
+            let i = bound.iter().position(|&b| b == name)?;
+            Some(values[i][assignment[i]])
+        };
+        let sets = inputs
+            .iter()
+            .map(|entry| match entry {
+                Entry::States(states) => *states,
+                Entry::Variable(name) => value(name).map(States::single).unwrap_or(variables[name]),
+            })
+            .collect();
+        let output = match output {
+            Entry::States(states) => states.iter().next(),
+            Entry::Variable(name) => value(name),
+        };
+        expanded.push((sets, output.expect("no state in output")));
+
+        // the next combination
+        let Some(i) = (0..bound.len()).find(|&i| assignment[i] + 1 < values[i].len()) else {
+            break;
+        };
+        assignment[i] += 1;
+        assignment[..i].fill(0);
+    }
+    Ok(expanded)
+}
+
+/// Permutations of the neighbours by the symmetries, like `rotate4` or `rotate8reflect`, where
+/// `permute` is given as an empty list
+fn symmetry_permutations(symmetries: &str, neighbours: usize) -> Result<Vec<Vec<usize>>> {
+    let invalid = || Error::msg(format!("unsupported symmetries '{}'.", symmetries));
+    let identity = (0..neighbours).collect::<Vec<_>>();
+    let (rotations, reflect) = match symmetries {
+        "none" => (1, false),
+        "permute" => return Ok(Vec::new()),
+        "reflect_horizontal" => (1, true),
+        _ => {
+            let rotate = symmetries.strip_prefix("rotate").ok_or_else(invalid)?;
+            let (rotations, reflect) = match rotate.strip_suffix("reflect") {
+                Some(rotations) => (rotations, true),
+                None => (rotate, false),
+            };
+            let rotations = rotations.parse::<usize>().map_err(|_| invalid())?;
+            if rotations == 0 || !neighbours.is_multiple_of(rotations) {
+                return Err(invalid());
+            }
+            (rotations, reflect)
+        }
+    };
+    let step = neighbours / rotations;
+    let mut permutations = Vec::new();
+    for r in 0..rotations {
+        let rotated = identity
+            .iter()
+            .map(|i| (i + r * step) % neighbours)
+            .collect::<Vec<_>>();
+        if reflect {
+            // mirrored along the axis through N
+            permutations.push(
+                identity
+                    .iter()
+                    .map(|&i| rotated[(neighbours - i) % neighbours])
+                    .collect(),
+            );
+        }
+        permutations.push(rotated);
+    }
+    Ok(permutations)
+}
+
+/// The sets of the cell and its neighbours permuted by the symmetries, or by all distinct
+/// permutations of the neighbours for no symmetries given
+fn apply_symmetries(inputs: &[States], symmetries: &[Vec<usize>]) -> Vec<Vec<States>> {
+    let (cell, neighbours) = (inputs[0], &inputs[1..]);
+    let with_cell = |neighbours: Vec<States>| [vec![cell], neighbours].concat();
+    if !symmetries.is_empty() {
+        return symmetries
+            .iter()
+            .map(|permutation| with_cell(permutation.iter().map(|&i| neighbours[i]).collect()))
+            .collect();
+    }
+
+    // distinct permutations in the lexicographic order
+    let mut permutation = neighbours.to_vec();
+    permutation.sort();
+    let mut permutations = vec![with_cell(permutation.clone())];
+    while let Some(i) = (1..permutation.len())
+        .rev()
+        .find(|&i| permutation[i - 1] < permutation[i])
+    {
+        let j = (i..permutation.len())
+            .rev()
+            .find(|&j| permutation[i - 1] < permutation[j])
+            .unwrap();
+        permutation.swap(i - 1, j);
+        permutation[i..].reverse();
+        permutations.push(with_cell(permutation.clone()));
+    }
+    permutations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pattern, CELL_DEAD};
+
+    const LIFE_TABLE: &str = "\
+@RULE LifeTable
+@TABLE
+n_states:2
+neighborhood:Moore
+symmetries:permute
+var a={0,1}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+0,1,1,1,0,0,0,0,0,1
+1,1,1,0,0,0,0,0,0,1
+1,1,1,1,0,0,0,0,0,1
+1,a,b,c,d,e,f,g,h,0
+
+@COLORS
+1 255 255 255
+";
+
+    #[test]
+    fn test_wireworld() -> Result<()> {
+        let wireworld: Rule = "WireWorld".parse()?;
+        assert_eq!(wireworld, Rule::wireworld());
+        assert_eq!(wireworld.to_string(), "Wireworld");
+        assert_eq!(wireworld.states(), 4);
+
+        // an electron moving right along a wire
+        let wire = Pattern::from_rle("x = 6, y = 1, rule = Wireworld\nBA4C!")?;
+        let mut world = World::new(8, 3, &[CELL_DEAD; 24])?.with_rule(wireworld.clone());
+        world.paste(&wire, 1, 1);
+        world.next();
+        assert_eq!(world.extract(1, 1, 6, 1).cells, [3, 2, 1, 3, 3, 3]);
+        world.next();
+        assert_eq!(world.extract(1, 1, 6, 1).cells, [3, 3, 2, 1, 3, 3]);
+
+        // a conductor with 3 heads around stays
+        let table = wireworld.table().unwrap();
+        assert_eq!(table.next(&[3, 1, 1, 0, 0, 0, 0, 0, 0]), 1);
+        assert_eq!(table.next(&[3, 1, 0, 2, 0, 0, 1, 0, 0]), 1);
+        assert_eq!(table.next(&[3, 1, 1, 1, 0, 0, 0, 0, 0]), 3);
+        assert_eq!(table.next(&[0, 1, 1, 0, 0, 0, 0, 0, 0]), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_life_table() -> Result<()> {
+        let table = RuleTable::parse(LIFE_TABLE)?;
+        assert_eq!(table.name(), "LifeTable");
+        // more than 64 transitions by the permutations
+        assert_eq!(table.outputs.len(), 56 + 28 + 56 + 1);
+
+        let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!")?;
+        let mut life = World::new(12, 12, &[CELL_DEAD; 144])?;
+        life.paste(&glider, 2, 2);
+        let mut world = life.clone().with_rule(Rule::from_table(table));
+        for _ in 0..12 {
+            life.next();
+            world.next();
+        }
+        assert_eq!(life.present_cells(), world.present_cells());
+
+        // parsed back from the rule file, not from the name
+        let rule: Rule = LIFE_TABLE.parse()?;
+        assert_eq!(rule.to_string(), "LifeTable");
+        assert_eq!(rule.to_definition().parse::<Rule>()?, rule);
+        assert_eq!(Rule::wireworld().to_definition(), "Wireworld");
+        assert!("LifeTable".parse::<Rule>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_symmetries() -> Result<()> {
+        // a cell becomes 2 with 1 in N, or with 1 in E and 0 or 1 in N
+        let table = RuleTable::parse(
+            "@RULE Turn\n@TABLE\nn_states:3\nneighborhood:vonNeumann\nsymmetries:none\n\
+             var x={1,2}\n0,1,0,0,0,2\n0,{0,1},1,0,0,2\nx,x,0,0,0,0\n",
+        )?;
+        assert_eq!(table.next(&[0, 1, 0, 0, 0]), 2);
+        assert_eq!(table.next(&[0, 0, 1, 0, 0]), 2);
+        assert_eq!(table.next(&[0, 0, 0, 1, 0]), 0);
+        // bound variables take the same state
+        assert_eq!(table.next(&[2, 2, 0, 0, 0]), 0);
+        assert_eq!(table.next(&[2, 1, 0, 0, 0]), 2);
+
+        let rotate4 = "@RULE R\n@TABLE\nn_states:2\nneighborhood:Moore\nsymmetries:rotate4\n\
+                       0,1,1,0,0,0,0,0,0,1\n";
+        let table = RuleTable::parse(rotate4)?;
+        assert_eq!(table.next(&[0, 0, 0, 1, 1, 0, 0, 0, 0]), 1);
+        assert_eq!(table.next(&[0, 0, 1, 1, 0, 0, 0, 0, 0]), 0);
+        let table = RuleTable::parse(&rotate4.replace("rotate4", "rotate4reflect"))?;
+        assert_eq!(table.next(&[0, 1, 0, 0, 0, 0, 0, 0, 1]), 1);
+
+        // digits without commas
+        let table = RuleTable::parse(
+            "@RULE D\n@TABLE\nn_states:2\nneighborhood:hexagonal\n\
+                                      symmetries:rotate6\n01000001\n",
+        )?;
+        assert_eq!(table.next(&[0, 0, 0, 1, 0, 0, 0]), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_table() {
+        let table = |body: &str| {
+            RuleTable::parse(&format!(
+                "@RULE T\n@TABLE\nn_states:2\nneighborhood:vonNeumann\n{}",
+                body
+            ))
+        };
+        assert!(table("0,1,0,0,0,1\n").is_ok());
+        assert!(table("0,1,0,0,1\n").is_err());
+        assert!(table("0,1,0,0,0,2\n").is_err());
+        assert!(table("0,y,0,0,0,1\n").is_err());
+        assert!(table("var a={0,1}\n0,a,0,0,0,a\n").is_ok());
+        assert!(table("var a={0,1}\nvar b={a}\n0,a,0,0,0,b\n").is_err());
+        assert!(table("0,{0,1},0,0,0,{0,1}\n").is_err());
+        assert!(table("symmetries:rotate8\n").is_err());
+        assert!(table("symmetries:mirror\n").is_err());
+        assert!(table("unknown:1\n").is_err());
+        assert!(RuleTable::parse("@RULE T\n@TREE\nnum_states=2\n").is_err());
+        assert!(RuleTable::parse("@TABLE\nn_states:2\n").is_err());
+        // names of other rules
+        assert!(RuleTable::parse(&WIREWORLD.replace("1,a,b,c,d,e,f,g,h,2", "")).is_err());
+        assert!(RuleTable::parse("@RULE B3/S23\n@TABLE\nn_states:2\n").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+use lifegame_core::World;
+
+use crate::render::{image_size, inner_region, rasterize, Raster, Region};
+use crate::style::Style;
+
+/// File format of animations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Gif,
+    Apng,
+}
+
+impl Format {
+    /// Format by the file extension, `.gif` or `.png`/`.apng`
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "gif" => Some(Format::Gif),
+            "png" | "apng" => Some(Format::Apng),
+            _ => None,
+        }
+    }
+}
+
+/// Animation of the generations `first..=last` counted from a world.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animation {
+    /// first rendered generation
+    pub first: usize,
+    /// last rendered generation
+    pub last: usize,
+    /// delay between frames in milliseconds
+    pub delay: u16,
+    /// rendered cells, or the whole world if `None`
+    pub region: Option<Region>,
+    pub style: Style,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            first: 0,
+            last: 100,
+            delay: 100,
+            region: None,
+            style: Style::default(),
+        }
+    }
+}
+
+impl Animation {
+    /// Frames rendered one generation at a time as they are taken, leaving the world untouched
+    pub fn frames(&self, world: &World) -> Result<impl Iterator<Item = Raster>> {
+        if self.first > self.last {
+            return Err(Error::msg("the first generation is after the last."));
+        }
+        let region = self.region.unwrap_or_else(|| inner_region(world));
+        let (width, height) = image_size(region, &self.style);
+        if width == 0 || height == 0 || width > u16::MAX as u32 || height > u16::MAX as u32 {
+            return Err(Error::msg(format!(
+                "invalid image size {}x{}.",
+                width, height
+            )));
+        }
+        let mut world = world.clone();
+        for _ in 0..self.first {
+            world.next();
+        }
+        let (first, style) = (self.first, self.style.clone());
+        Ok((first..=self.last).map(move |generation| {
+            if generation > first {
+                world.next();
+            }
+            rasterize(&world, region, &style)
+        }))
+    }
+
+    /// Write the animation of the world, encoding a frame at a time
+    pub fn write<W: Write>(&self, world: &World, format: Format, writer: W) -> Result<()> {
+        let frames = self.frames(world)?;
+        match format {
+            Format::Gif => self.write_gif(frames, writer),
+            Format::Apng => self.write_apng(frames, writer),
+        }
+    }
+
+    fn write_gif<W: Write>(&self, frames: impl Iterator<Item = Raster>, writer: W) -> Result<()> {
+        let mut frames = frames.peekable();
+        let raster = frames.peek().expect("at least a frame");
+        let (width, height) = (raster.width as u16, raster.height as u16);
+        let mut encoder = gif::Encoder::new(writer, width, height, &self.style.palette())?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for raster in frames {
+            let mut frame = gif::Frame::from_indexed_pixels(width, height, &*raster.pixels, None);
+            // GIF delays are in centiseconds.
+            frame.delay = self.delay.div_ceil(10);
+            encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    fn write_apng<W: Write>(&self, frames: impl Iterator<Item = Raster>, writer: W) -> Result<()> {
+        let mut frames = frames.peekable();
+        let raster = frames.peek().expect("at least a frame");
+        let mut encoder = png::Encoder::new(writer, raster.width, raster.height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(self.style.palette());
+        encoder.set_animated((self.last - self.first + 1) as u32, 0)?;
+        encoder.set_frame_delay(self.delay, 1000)?;
+        let mut writer = encoder.write_header()?;
+        for raster in frames {
+            writer.write_image_data(&raster.pixels)?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lifegame_core::{Pattern, CELL_DEAD};
+
+    fn glider_world() -> World {
+        let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        let mut world = World::new(10, 10, &[CELL_DEAD; 100]).unwrap();
+        world.paste(&glider, 1, 1);
+        world
+    }
+
+    #[test]
+    fn test_frames() {
+        let world = glider_world();
+        let animation = Animation {
+            first: 2,
+            last: 6,
+            ..Animation::default()
+        };
+        let frames = animation.frames(&world).unwrap().collect::<Vec<_>>();
+        assert_eq!(frames.len(), 5);
+        // the glider moves by a cell in 4 generations.
+        let shifted = |raster: &Raster| {
+            let width = raster.width as usize;
+            let mut pixels = vec![0; raster.pixels.len()];
+            for (i, &p) in raster.pixels.iter().enumerate() {
+                let (x, y) = (i % width, i / width);
+                if x + 4 < width && (y + 4) * width < raster.pixels.len() {
+                    pixels[(y + 4) * width + x + 4] = p;
+                }
+            }
+            pixels
+        };
+        assert_eq!(shifted(&frames[0]), frames[4].pixels);
+        assert_eq!(world.generation(), 0);
+        assert!(Animation {
+            first: 3,
+            last: 2,
+            ..Animation::default()
+        }
+        .frames(&world)
+        .is_err());
+    }
+
+    #[test]
+    fn test_write_gif() {
+        let world = glider_world();
+        let animation = Animation {
+            last: 3,
+            ..Animation::default()
+        };
+        let mut buffer = Vec::new();
+        animation.write(&world, Format::Gif, &mut buffer).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new().read_info(&buffer[..]).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (32, 32));
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 10);
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn test_write_apng() {
+        let world = glider_world();
+        let animation = Animation {
+            last: 3,
+            delay: 50,
+            ..Animation::default()
+        };
+        let mut buffer = Vec::new();
+        animation.write(&world, Format::Apng, &mut buffer).unwrap();
+
+        let decoder = png::Decoder::new(&buffer[..]);
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (32, 32));
+        assert_eq!(info.animation_control.unwrap().num_frames, 4);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path(Path::new("a.GIF")), Some(Format::Gif));
+        assert_eq!(Format::from_path(Path::new("a.apng")), Some(Format::Apng));
+        assert_eq!(Format::from_path(Path::new("a.rle")), None);
+    }
+}
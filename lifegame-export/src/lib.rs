@@ -0,0 +1,8 @@
+pub mod animation;
+pub mod render;
+pub mod snapshot;
+pub mod style;
+
+pub use animation::{Animation, Format};
+pub use snapshot::{ImageFormat, Snapshot};
+pub use style::Style;
@@ -0,0 +1,175 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use anyhow::{Context, Error, Result};
+use lifegame_core::{Pattern, Rule, World, CELL_DEAD};
+use lifegame_export::render::Region;
+use lifegame_export::style::parse_color;
+use lifegame_export::{Animation, Format, ImageFormat, Snapshot, Style};
+
+const USAGE: &str = "\
+usage: lifegame-export animate INPUT OUTPUT [OPTIONS]
+       lifegame-export snapshot INPUT OUTPUT [OPTIONS]
+
+animate renders a pattern file (RLE or plaintext) into an animated GIF or APNG,
+chosen by the extension of OUTPUT (.gif, .png or .apng).
+snapshot renders a generation of the pattern into a PNG or SVG (.png or .svg).
+
+options:
+  --first N          first generation of animations (default 0)
+  --last N           last generation of animations (default 100)
+  --delay MS         delay between frames in milliseconds (default 100)
+  --generation N     generation of snapshots (default 0)
+  --rule RULE        rule like B3/S23 (default the rule of INPUT or B3/S23)
+  --region X,Y,W,H   cells relative to the top-left of the pattern (default all)
+  --cell-size PX     pixels per cell (default 4)
+  --alive #rrggbb    color of alive cells (default #000000)
+  --dead #rrggbb     color of dead cells (default #ffffff)
+  --grid #rrggbb     draw grid lines in the color
+  --margin N         dead cells around the pattern (default 10)";
+
+/// Exported image.
+enum Command {
+    Animate,
+    Snapshot,
+}
+
+/// Command line options.
+struct Args {
+    command: Command,
+    input: PathBuf,
+    output: PathBuf,
+    margin: usize,
+    region: Option<Region>,
+    first: usize,
+    last: usize,
+    delay: u16,
+    generation: usize,
+    rule: Option<Rule>,
+    style: Style,
+}
+
+impl Args {
+    fn parse() -> Result<Self> {
+        let mut iter = std::env::args().skip(1);
+        let command = match iter.next().as_deref() {
+            Some("animate") => Command::Animate,
+            Some("snapshot") => Command::Snapshot,
+            Some("-h" | "--help") => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ => return Err(Error::msg(USAGE)),
+        };
+        let animation = Animation::default();
+        let mut paths = Vec::new();
+        let mut args = Self {
+            command,
+            input: PathBuf::new(),
+            output: PathBuf::new(),
+            margin: 10,
+            region: None,
+            first: animation.first,
+            last: animation.last,
+            delay: animation.delay,
+            generation: 0,
+            rule: None,
+            style: animation.style,
+        };
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with("--") {
+                paths.push(PathBuf::from(arg));
+                continue;
+            }
+            let value = iter
+                .next()
+                .with_context(|| format!("missing value of {}", arg))?;
+            let style = &mut args.style;
+            match arg.as_str() {
+                "--first" => args.first = value.parse()?,
+                "--last" => args.last = value.parse()?,
+                "--delay" => args.delay = value.parse()?,
+                "--generation" => args.generation = value.parse()?,
+                "--rule" => args.rule = Some(value.parse()?),
+                "--region" => args.region = Some(parse_region(&value)?),
+                "--cell-size" => style.cell_size = value.parse()?,
+                "--alive" => style.alive = parse_color(&value)?,
+                "--dead" => style.dead = parse_color(&value)?,
+                "--grid" => style.grid = Some(parse_color(&value)?),
+                "--margin" => args.margin = value.parse()?,
+                _ => return Err(Error::msg(format!("unknown option {}\n\n{}", arg, USAGE))),
+            }
+        }
+        [args.input, args.output] = paths.try_into().map_err(|_| Error::msg(USAGE))?;
+        Ok(args)
+    }
+}
+
+/// Parse a region written as `X,Y,W,H`
+fn parse_region(text: &str) -> Result<Region> {
+    let values = text
+        .split(',')
+        .map(|value| value.trim().parse())
+        .collect::<Result<Vec<usize>, _>>()
+        .with_context(|| format!("invalid region {}", text))?;
+    match values[..] {
+        [x, y, width, height] => Ok((x, y, width, height)),
+        _ => Err(Error::msg(format!("invalid region {}", text))),
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse()?;
+    let text = fs::read_to_string(&args.input)
+        .with_context(|| format!("cannot read {}", args.input.display()))?;
+    let pattern = Pattern::parse(&text)?;
+    let rule = match (args.rule, &pattern.rule) {
+        (Some(rule), _) => rule,
+        (None, Some(rule)) => rule.parse()?,
+        (None, None) => Rule::default(),
+    };
+
+    // the pattern surrounded by the margin and the always-dead boundary
+    let offset = args.margin + 1;
+    let nx = pattern.width + 2 * offset;
+    let ny = pattern.height + 2 * offset;
+    let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny])?.with_rule(rule);
+    world.paste(&pattern, offset, offset);
+    let region = args
+        .region
+        .map(|(x, y, width, height)| (x + offset, y + offset, width, height));
+
+    let create = || {
+        File::create(&args.output)
+            .map(BufWriter::new)
+            .with_context(|| format!("cannot create {}", args.output.display()))
+    };
+    match args.command {
+        Command::Animate => {
+            let format = Format::from_path(&args.output)
+                .with_context(|| format!("unknown format of {}", args.output.display()))?;
+            let animation = Animation {
+                first: args.first,
+                last: args.last,
+                delay: args.delay,
+                region,
+                style: args.style,
+            };
+            animation.write(&world, format, create()?)?;
+        }
+        Command::Snapshot => {
+            let format = ImageFormat::from_path(&args.output)
+                .with_context(|| format!("unknown format of {}", args.output.display()))?;
+            for _ in 0..args.generation {
+                world.next();
+            }
+            let snapshot = Snapshot {
+                region,
+                style: args.style,
+            };
+            snapshot.write(&world, format, create()?)?;
+        }
+    }
+    Ok(())
+}
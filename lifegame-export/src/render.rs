@@ -0,0 +1,121 @@
+use lifegame_core::{World, CELL_ALIVE, CELL_DEAD};
+
+use crate::style::Style;
+
+/// Palette index of dead cells
+pub const DEAD: u8 = 0;
+/// Palette index of alive cells
+pub const ALIVE: u8 = 1;
+/// Palette index of grid lines
+pub const GRID: u8 = 2;
+/// Palette index of the other states, like dying cells of Generations rules
+pub const DYING: u8 = 3;
+
+/// Rectangle of cells as `(ix, iy, width, height)`
+pub type Region = (usize, usize, usize, usize);
+
+/// Image of palette indices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Raster {
+    pub width: u32,
+    pub height: u32,
+    /// palette indices row by row
+    pub pixels: Vec<u8>,
+}
+
+/// The whole world excluding the always-dead boundary
+pub fn inner_region(world: &World) -> Region {
+    (1, 1, world.nx() - 2, world.ny() - 2)
+}
+
+/// Size of the image of the region in pixels.
+///
+/// Grid lines take the first pixel row and column of every cell, and one more
+/// row and column close the grid at the bottom-right.
+pub fn image_size(region: Region, style: &Style) -> (u32, u32) {
+    let (_, _, width, height) = region;
+    let border = style.grid.is_some() as u32;
+    (
+        width as u32 * style.cell_size + border,
+        height as u32 * style.cell_size + border,
+    )
+}
+
+/// Render the cells of the region, clipped to the world
+pub fn rasterize(world: &World, region: Region, style: &Style) -> Raster {
+    let (ix, iy, _, _) = region;
+    let (width, height) = image_size(region, style);
+    let size = style.cell_size.max(1);
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for py in 0..height {
+        for px in 0..width {
+            if style.grid.is_some() && (px % size == 0 || py % size == 0) {
+                pixels.push(GRID);
+                continue;
+            }
+            let (x, y) = (ix + (px / size) as usize, iy + (py / size) as usize);
+            let cell = match x < world.nx() && y < world.ny() {
+                true => world.get_present_cell(x, y),
+                false => CELL_DEAD,
+            };
+            pixels.push(match cell {
+                CELL_DEAD => DEAD,
+                CELL_ALIVE => ALIVE,
+                _ => DYING,
+            });
+        }
+    }
+    Raster {
+        width,
+        height,
+        pixels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rasterize() {
+        let mut world = World::new(4, 4, &[CELL_DEAD; 16]).unwrap();
+        world.set_present_cell(1, 1, CELL_ALIVE);
+        let style = Style {
+            cell_size: 2,
+            ..Style::default()
+        };
+        let raster = rasterize(&world, inner_region(&world), &style);
+        assert_eq!((raster.width, raster.height), (4, 4));
+        assert_eq!(
+            raster.pixels,
+            [
+                [ALIVE, ALIVE, DEAD, DEAD],
+                [ALIVE, ALIVE, DEAD, DEAD],
+                [DEAD; 4],
+                [DEAD; 4],
+            ]
+            .concat()
+        );
+
+        let style = Style {
+            cell_size: 2,
+            grid: Some([128, 128, 128]),
+            ..Style::default()
+        };
+        let raster = rasterize(&world, (1, 1, 2, 1), &style);
+        assert_eq!((raster.width, raster.height), (5, 3));
+        assert_eq!(
+            raster.pixels,
+            [[GRID; 5], [GRID, ALIVE, GRID, DEAD, GRID], [GRID; 5]].concat()
+        );
+
+        // dying cells of Generations rules
+        let mut world = world.with_rule("/2/3".parse().unwrap());
+        world.set_present_cell(2, 1, 2);
+        let raster = rasterize(&world, (1, 1, 3, 1), &Style::default());
+        assert_eq!(
+            raster.pixels[..12],
+            [ALIVE, ALIVE, ALIVE, ALIVE, DYING, DYING, DYING, DYING, DEAD, DEAD, DEAD, DEAD]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+use lifegame_core::{Cell, World, CELL_ALIVE, CELL_DEAD};
+
+use crate::render::{image_size, inner_region, rasterize, Region};
+use crate::style::{format_color, Style};
+
+/// File format of still images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    /// Format by the file extension, `.png` or `.svg`
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// Still image of the present generation of a world.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// rendered cells, or the whole world if `None`
+    pub region: Option<Region>,
+    pub style: Style,
+}
+
+impl Snapshot {
+    /// Write the image of the world
+    pub fn write<W: Write>(&self, world: &World, format: ImageFormat, writer: W) -> Result<()> {
+        match format {
+            ImageFormat::Png => self.write_png(world, writer),
+            ImageFormat::Svg => self.write_svg(world, writer),
+        }
+    }
+
+    fn region(&self, world: &World) -> Result<Region> {
+        let region = self.region.unwrap_or_else(|| inner_region(world));
+        let (width, height) = image_size(region, &self.style);
+        if width == 0 || height == 0 {
+            return Err(Error::msg(format!(
+                "invalid image size {}x{}.",
+                width, height
+            )));
+        }
+        Ok(region)
+    }
+
+    /// Write the image in PNG, scaled by the cell size
+    pub fn write_png<W: Write>(&self, world: &World, writer: W) -> Result<()> {
+        let raster = rasterize(world, self.region(world)?, &self.style);
+        let mut encoder = png::Encoder::new(writer, raster.width, raster.height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(self.style.palette());
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&raster.pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Write the image in SVG, with a rectangle per horizontal run of alive or dying cells
+    pub fn write_svg<W: Write>(&self, world: &World, mut writer: W) -> Result<()> {
+        let region = self.region(world)?;
+        let (ix, iy, width, height) = region;
+        let size = self.style.cell_size;
+        let (image_width, image_height) = (width as u32 * size, height as u32 * size);
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" shape-rendering="crispEdges">"#,
+            w = image_width,
+            h = image_height
+        )?;
+        writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="{}"/>"#,
+            image_width,
+            image_height,
+            format_color(self.style.dead)
+        )?;
+        // dying cells of Generations rules and other states of rule tables are grouped apart
+        let mut groups = vec![(
+            self.style.alive,
+            runs(world, region, |cell| cell == CELL_ALIVE),
+        )];
+        let dying = runs(world, region, |cell| {
+            cell != CELL_DEAD && cell != CELL_ALIVE
+        });
+        if !dying.is_empty() {
+            groups.push((self.style.dying(), dying));
+        }
+        for (color, runs) in groups {
+            writeln!(svg, r#"<g fill="{}">"#, format_color(color))?;
+            for (x, y, run) in runs {
+                writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                    (x - ix) as u32 * size,
+                    (y - iy) as u32 * size,
+                    run as u32 * size,
+                    size
+                )?;
+            }
+            writeln!(svg, "</g>")?;
+        }
+        if let Some(grid) = self.style.grid {
+            let mut path = String::new();
+            for x in 0..=width as u32 {
+                write!(path, "M{} 0V{}", x * size, image_height)?;
+            }
+            for y in 0..=height as u32 {
+                write!(path, "M0 {}H{}", y * size, image_width)?;
+            }
+            writeln!(
+                svg,
+                r#"<path d="{}" stroke="{}" stroke-width="1" fill="none"/>"#,
+                path,
+                format_color(grid)
+            )?;
+        }
+        writeln!(svg, "</svg>")?;
+        writer.write_all(svg.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Horizontal runs of the matching cells in the region as `(ix, iy, length)`, clipped to the
+/// world
+fn runs(
+    world: &World,
+    region: Region,
+    matches: impl Fn(Cell) -> bool,
+) -> Vec<(usize, usize, usize)> {
+    let (ix, iy, width, height) = region;
+    let xs = ix.min(world.nx())..ix.saturating_add(width).min(world.nx());
+    let ys = iy.min(world.ny())..iy.saturating_add(height).min(world.ny());
+    let cells = ys.flat_map(|y| xs.clone().map(move |x| (x, y)));
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+    for (x, y) in cells.filter(|&(x, y)| matches(world.get_present_cell(x, y))) {
+        match runs.last_mut() {
+            Some((rx, ry, length)) if *ry == y && *rx + *length == x => *length += 1,
+            _ => runs.push((x, y, 1)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lifegame_core::{Pattern, CELL_DEAD};
+
+    fn world() -> World {
+        let pattern = Pattern::from_rle("x = 5, y = 2\n3obo$bo!").unwrap();
+        let mut world = World::new(8, 5, &[CELL_DEAD; 40]).unwrap();
+        world.paste(&pattern, 1, 1);
+        world
+    }
+
+    #[test]
+    fn test_runs() {
+        let alive = |cell| cell == CELL_ALIVE;
+        assert_eq!(
+            runs(&world(), (1, 1, 6, 3), alive),
+            [(1, 1, 3), (5, 1, 1), (2, 2, 1)]
+        );
+        assert_eq!(runs(&world(), (2, 1, 2, 1), alive), [(2, 1, 2)]);
+    }
+
+    #[test]
+    fn test_write_svg() {
+        let snapshot = Snapshot {
+            region: None,
+            style: Style {
+                cell_size: 10,
+                ..Style::default()
+            },
+        };
+        let mut buffer = Vec::new();
+        snapshot
+            .write(&world(), ImageFormat::Svg, &mut buffer)
+            .unwrap();
+        let svg = String::from_utf8(buffer).unwrap();
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="60" height="30""#)
+        );
+        assert!(svg.contains(r#"<rect x="0" y="0" width="30" height="10"/>"#));
+        assert!(svg.contains(r#"<rect x="40" y="0" width="10" height="10"/>"#));
+        assert!(svg.contains(r#"<rect x="10" y="10" width="10" height="10"/>"#));
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert!(!svg.contains("<path"));
+
+        // dying cells of Generations rules
+        let mut world = world().with_rule("/2/3".parse().unwrap());
+        world.set_present_cell(5, 1, 2);
+        let mut buffer = Vec::new();
+        snapshot
+            .write(&world, ImageFormat::Svg, &mut buffer)
+            .unwrap();
+        let svg = String::from_utf8(buffer).unwrap();
+        assert!(svg
+            .contains("<g fill=\"#7f7f7f\">\n<rect x=\"40\" y=\"0\" width=\"10\" height=\"10\"/>"));
+        assert_eq!(svg.matches("<rect").count(), 4);
+    }
+
+    #[test]
+    fn test_write_png() {
+        let snapshot = Snapshot {
+            region: Some((1, 1, 3, 2)),
+            style: Style {
+                cell_size: 3,
+                ..Style::default()
+            },
+        };
+        let mut buffer = Vec::new();
+        snapshot
+            .write(&world(), ImageFormat::Png, &mut buffer)
+            .unwrap();
+
+        let decoder = png::Decoder::new(&buffer[..]);
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (9, 6));
+        assert!(reader.info().animation_control.is_none());
+        assert_eq!(pixels[..9], [1; 9]);
+        assert_eq!(pixels[9 * 3..9 * 4], [0, 0, 0, 1, 1, 1, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::{Error, Result};
+
+/// Color as red, green and blue
+pub type Rgb = [u8; 3];
+
+/// Appearance of exported images.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Style {
+    /// pixels per cell along with each axis
+    pub cell_size: u32,
+    /// color of alive cells
+    pub alive: Rgb,
+    /// color of dead cells
+    pub dead: Rgb,
+    /// color of grid lines between cells, or `None` without grid lines
+    pub grid: Option<Rgb>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            cell_size: 4,
+            alive: [0x00, 0x00, 0x00],
+            dead: [0xff, 0xff, 0xff],
+            grid: None,
+        }
+    }
+}
+
+impl Style {
+    /// Palette indexed by [`DEAD`](crate::render::DEAD), [`ALIVE`](crate::render::ALIVE),
+    /// [`GRID`](crate::render::GRID) and [`DYING`](crate::render::DYING), flattened as RGB
+    /// triples
+    pub fn palette(&self) -> Vec<u8> {
+        [
+            self.dead,
+            self.alive,
+            self.grid.unwrap_or(self.dead),
+            self.dying(),
+        ]
+        .concat()
+    }
+
+    /// Color of the states other than dead and alive, halfway between the alive and dead colors
+    pub fn dying(&self) -> Rgb {
+        let mut color = [0; 3];
+        for (c, (alive, dead)) in color.iter_mut().zip(self.alive.iter().zip(self.dead)) {
+            *c = ((*alive as u16 + dead as u16) / 2) as u8;
+        }
+        color
+    }
+}
+
+/// Parse a color written as `#rrggbb`
+pub fn parse_color(text: &str) -> Result<Rgb> {
+    let hex = text.trim().strip_prefix('#').unwrap_or(text.trim());
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::msg(format!("invalid color: {}", text)));
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    Ok([channel(0), channel(2), channel(4)])
+}
+
+/// Format a color as `#rrggbb`
+pub fn format_color(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff8000").unwrap(), [255, 128, 0]);
+        assert_eq!(parse_color("0A0b0C").unwrap(), [10, 11, 12]);
+        assert!(parse_color("#fff").is_err());
+        assert!(parse_color("#gggggg").is_err());
+        assert_eq!(format_color([255, 128, 0]), "#ff8000");
+    }
+
+    #[test]
+    fn test_palette() {
+        let style = Style::default();
+        assert_eq!(style.dying(), [0x7f, 0x7f, 0x7f]);
+        assert_eq!(style.palette().len(), 4 * 3);
+    }
+}
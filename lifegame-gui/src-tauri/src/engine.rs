@@ -0,0 +1,507 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+use lifegame_core::{Cell, Pattern, Rule, World, CELL_ALIVE, CELL_DEAD};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Largest seed exactly representable as a JavaScript number, so that the webview shows and
+/// sends back the same seed
+pub const MAX_SEED: u64 = (1 << 53) - 1;
+
+/// Settings of a new world sent by the webview.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldConfig {
+    /// world size along with x-axis, including the boundary
+    pub width: usize,
+    /// world size along with y-axis, including the boundary
+    pub height: usize,
+    /// alive cell probability of the random soup
+    pub density: f64,
+    /// seed of the random soup, chosen randomly if not given
+    pub seed: Option<u64>,
+    /// rule like `B3/S23`, B3/S23 if not given
+    pub rule: Option<String>,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            width: 120,
+            height: 80,
+            density: 0.2,
+            seed: None,
+            rule: None,
+        }
+    }
+}
+
+/// Summary of the world sent to the webview.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldInfo {
+    pub width: usize,
+    pub height: usize,
+    pub generation: usize,
+    pub population: usize,
+    pub rule: String,
+    pub seed: u64,
+}
+
+/// Rectangle of cells, a viewport or a selection of the webview.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rect {
+    pub ix: usize,
+    pub iy: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Transformation of a selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Transform {
+    RotateClockwise,
+    RotateCounterclockwise,
+    FlipHorizontally,
+    FlipVertically,
+}
+
+/// The simulated world owned by the backend.
+#[derive(Debug)]
+pub struct Engine {
+    world: World,
+    seed: u64,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new(&WorldConfig::default()).expect("invalid default world")
+    }
+}
+
+impl Engine {
+    /// Create a world of a random soup
+    pub fn new(config: &WorldConfig) -> Result<Engine> {
+        if !(0.0..=1.0).contains(&config.density) {
+            return Err(Error::msg("density must be between 0 and 1."));
+        }
+        let rule: Rule = match &config.rule {
+            Some(rule) => rule.parse()?,
+            None => Rule::default(),
+        };
+        let seed = match config.seed {
+            Some(seed) if seed > MAX_SEED => {
+                return Err(Error::msg(format!("seed must be {} or smaller.", MAX_SEED)))
+            }
+            Some(seed) => seed,
+            None => rand::thread_rng().gen_range(0..=MAX_SEED),
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let cells = (0..config.width * config.height)
+            .map(|_| match rng.gen_bool(config.density) {
+                true => CELL_ALIVE,
+                false => CELL_DEAD,
+            })
+            .collect::<Vec<_>>();
+        let world = World::new(config.width, config.height, &cells)?.with_rule(rule);
+        Ok(Engine { world, seed })
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn info(&self) -> WorldInfo {
+        WorldInfo {
+            width: self.world.nx(),
+            height: self.world.ny(),
+            generation: self.world.generation(),
+            population: self.world.population(),
+            rule: self.world.rule().to_string(),
+            seed: self.seed,
+        }
+    }
+
+    /// Read a RLE, plaintext or macrocell file and load the pattern
+    pub fn open_pattern(&mut self, path: &Path) -> Result<()> {
+        let text = fs::read_to_string(path)?;
+        let pattern = Pattern::parse(&text)?;
+        self.load_pattern(&pattern)
+    }
+
+    /// Replace the world with the pattern at its center, taking the rule of the pattern if any.
+    /// The world grows if the pattern does not fit.
+    pub fn load_pattern(&mut self, pattern: &Pattern) -> Result<()> {
+        let rule = match &pattern.rule {
+            Some(rule) => rule.parse()?,
+            None => self.world.rule().clone(),
+        };
+        let nx = self.world.nx().max(pattern.width + 2);
+        let ny = self.world.ny().max(pattern.height + 2);
+        let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny])?.with_rule(rule);
+        world.paste(pattern, (nx - pattern.width) / 2, (ny - pattern.height) / 2);
+        self.world = world;
+        Ok(())
+    }
+
+    /// Save the selection, or the whole world without the boundary if nothing is selected.
+    /// The format is plaintext for `.cells` and `.txt` files, and RLE otherwise.
+    pub fn save_pattern(&self, path: &Path, selection: Option<Rect>) -> Result<()> {
+        let mut pattern = self.extract(selection.unwrap_or(Rect {
+            ix: 1,
+            iy: 1,
+            width: self.world.nx() - 2,
+            height: self.world.ny() - 2,
+        }));
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        let text = match extension.as_deref() {
+            Some("cells") | Some("txt") => pattern.to_plaintext(),
+            _ => {
+                pattern.rule = Some(self.world.rule().to_definition());
+                pattern.to_rle()
+            }
+        };
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Advance the world by the number of generations
+    pub fn step(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.world.next();
+        }
+    }
+
+    /// Flip the cell between alive and dead, returning the new state.
+    /// Cells on the boundary stay dead.
+    pub fn toggle_cell(&mut self, ix: usize, iy: usize) -> Result<Cell> {
+        self.check_inner(ix, iy)?;
+        let cell = match self.world.get_present_cell(ix, iy) {
+            CELL_DEAD => CELL_ALIVE,
+            _ => CELL_DEAD,
+        };
+        self.world.set_present_cell(ix, iy, cell);
+        Ok(cell)
+    }
+
+    /// Set the cells alive or dead, ignoring cells out of the world
+    pub fn set_cells(&mut self, cells: &[[usize; 2]], alive: bool) {
+        let cell = if alive { CELL_ALIVE } else { CELL_DEAD };
+        for &[ix, iy] in cells {
+            if ix < self.world.nx() && iy < self.world.ny() {
+                self.world.set_present_cell(ix, iy, cell);
+            }
+        }
+    }
+
+    /// Set the cells on the line segment between the two cells, ends included
+    pub fn draw_line(&mut self, from: [usize; 2], to: [usize; 2], alive: bool) {
+        let cell = if alive { CELL_ALIVE } else { CELL_DEAD };
+        // Bresenham's line algorithm
+        let [mut x, mut y] = from.map(|n| n as isize);
+        let [x1, y1] = to.map(|n| n as isize);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut error = dx + dy;
+        loop {
+            self.world.set_present_cell(x as usize, y as usize, cell);
+            if (x, y) == (x1, y1) {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Alive cells in the viewport
+    pub fn live_cells(&self, viewport: Rect) -> Vec<[usize; 2]> {
+        self.world
+            .alive_cells_in(viewport.ix, viewport.iy, viewport.width, viewport.height)
+            .map(|(ix, iy)| [ix, iy])
+            .collect()
+    }
+
+    /// Kill all cells in the selection
+    pub fn clear(&mut self, selection: Rect) {
+        let pattern = self.extract(selection);
+        let dead = vec![CELL_DEAD; pattern.cells.len()];
+        let dead =
+            Pattern::new(pattern.width, pattern.height, &dead).expect("invalid pattern size");
+        self.world.paste(&dead, selection.ix, selection.iy);
+    }
+
+    /// Cells in the selection as RLE
+    pub fn copy(&self, selection: Rect) -> String {
+        let mut pattern = self.extract(selection);
+        pattern.rule = Some(self.world.rule().to_definition());
+        pattern.to_rle()
+    }
+
+    /// Paste RLE or plaintext with its top-left corner at (ix, iy), returning the pasted rectangle.
+    /// Cells outside the world are dropped.
+    pub fn paste(&mut self, text: &str, ix: usize, iy: usize) -> Result<Rect> {
+        let pattern = Pattern::parse(text)?;
+        self.world.paste(&pattern, ix, iy);
+        Ok(self.clip(Rect {
+            ix,
+            iy,
+            width: pattern.width,
+            height: pattern.height,
+        }))
+    }
+
+    /// Transform the cells in the selection keeping its top-left corner, returning the new selection
+    pub fn transform(&mut self, selection: Rect, transform: Transform) -> Rect {
+        let pattern = self.extract(selection);
+        let pattern = match transform {
+            Transform::RotateClockwise => pattern.rotated_clockwise(),
+            Transform::RotateCounterclockwise => pattern.rotated_counterclockwise(),
+            Transform::FlipHorizontally => pattern.flipped_horizontally(),
+            Transform::FlipVertically => pattern.flipped_vertically(),
+        };
+        self.clear(selection);
+        self.world.paste(&pattern, selection.ix, selection.iy);
+        self.clip(Rect {
+            ix: selection.ix,
+            iy: selection.iy,
+            width: pattern.width,
+            height: pattern.height,
+        })
+    }
+
+    fn extract(&self, selection: Rect) -> Pattern {
+        self.world.extract(
+            selection.ix,
+            selection.iy,
+            selection.width,
+            selection.height,
+        )
+    }
+
+    /// Clip the rectangle to the world
+    fn clip(&self, rect: Rect) -> Rect {
+        let (ix, iy) = (rect.ix.min(self.world.nx()), rect.iy.min(self.world.ny()));
+        Rect {
+            ix,
+            iy,
+            width: rect.width.min(self.world.nx() - ix),
+            height: rect.height.min(self.world.ny() - iy),
+        }
+    }
+
+    fn check_inner(&self, ix: usize, iy: usize) -> Result<()> {
+        if !self.world.is_inner(ix, iy) {
+            return Err(Error::msg(format!(
+                "cell ({}, {}) is out of the world.",
+                ix, iy
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WorldConfig {
+        WorldConfig {
+            width: 20,
+            height: 10,
+            density: 0.3,
+            seed: Some(7),
+            rule: Some("B36/S23".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_create_world() {
+        let engine = Engine::new(&config()).unwrap();
+        let info = engine.info();
+        assert_eq!((info.width, info.height, info.generation), (20, 10, 0));
+        assert_eq!(info.rule, "B36/S23");
+        assert!(info.population > 0);
+        // the same seed makes the same soup.
+        let same = Engine::new(&config()).unwrap();
+        assert_eq!(
+            same.world().extract(0, 0, 20, 10),
+            engine.world().extract(0, 0, 20, 10)
+        );
+
+        let invalid = WorldConfig {
+            rule: Some("B9/S".to_string()),
+            ..config()
+        };
+        assert!(Engine::new(&invalid).is_err());
+        let invalid = WorldConfig {
+            density: 1.5,
+            ..config()
+        };
+        assert!(Engine::new(&invalid).is_err());
+        let invalid = WorldConfig {
+            seed: Some(MAX_SEED + 1),
+            ..config()
+        };
+        assert!(Engine::new(&invalid).is_err());
+        let random = WorldConfig {
+            seed: None,
+            ..config()
+        };
+        assert!(Engine::new(&random).unwrap().info().seed <= MAX_SEED);
+    }
+
+    #[test]
+    fn test_edit_and_step() {
+        let mut engine = Engine::new(&WorldConfig {
+            density: 0.0,
+            ..config()
+        })
+        .unwrap();
+        engine.set_cells(&[[4, 5], [5, 5], [6, 5], [100, 100]], true);
+        assert_eq!(engine.toggle_cell(5, 4).unwrap(), CELL_ALIVE);
+        assert_eq!(engine.toggle_cell(5, 4).unwrap(), CELL_DEAD);
+        assert!(engine.toggle_cell(0, 0).is_err());
+
+        engine.step(1);
+        let viewport = Rect {
+            ix: 0,
+            iy: 0,
+            width: 20,
+            height: 10,
+        };
+        assert_eq!(engine.live_cells(viewport), [[5, 4], [5, 5], [5, 6]]);
+        assert_eq!(engine.info().generation, 1);
+        let viewport = Rect {
+            ix: 5,
+            iy: 5,
+            width: 3,
+            height: 3,
+        };
+        assert_eq!(engine.live_cells(viewport), [[5, 5], [5, 6]]);
+    }
+
+    #[test]
+    fn test_editor() {
+        let mut engine = Engine::new(&WorldConfig {
+            density: 0.0,
+            ..config()
+        })
+        .unwrap();
+        let all = Rect {
+            ix: 0,
+            iy: 0,
+            width: 20,
+            height: 10,
+        };
+        engine.draw_line([1, 1], [4, 3], true);
+        assert_eq!(engine.live_cells(all), [[1, 1], [2, 2], [3, 2], [4, 3]]);
+        engine.draw_line([2, 2], [3, 2], false);
+        assert_eq!(engine.live_cells(all), [[1, 1], [4, 3]]);
+
+        let selection = Rect {
+            ix: 1,
+            iy: 1,
+            width: 4,
+            height: 3,
+        };
+        let rle = engine.copy(selection);
+        assert_eq!(rle, "x = 4, y = 3, rule = B36/S23\no2$3bo!\n");
+        let rotated = engine.transform(selection, Transform::RotateClockwise);
+        assert_eq!(
+            rotated,
+            Rect {
+                ix: 1,
+                iy: 1,
+                width: 3,
+                height: 4
+            }
+        );
+        assert_eq!(engine.live_cells(all), [[3, 1], [1, 4]]);
+        let flipped = engine.transform(rotated, Transform::FlipVertically);
+        assert_eq!(flipped, rotated);
+        assert_eq!(engine.live_cells(all), [[1, 1], [3, 4]]);
+
+        engine.clear(all);
+        assert!(engine.live_cells(all).is_empty());
+
+        // pasted cells beyond the world are dropped.
+        let pasted = engine.paste(&rle, 17, 8).unwrap();
+        assert_eq!(
+            pasted,
+            Rect {
+                ix: 17,
+                iy: 8,
+                width: 3,
+                height: 2
+            }
+        );
+        assert_eq!(engine.live_cells(all), [[17, 8]]);
+        assert!(engine.paste("x = 1\n!", 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_open_and_save() -> Result<()> {
+        let mut engine = Engine::new(&config())?;
+        let path =
+            std::env::temp_dir().join(format!("lifegame-gui-test-{}.mc", std::process::id()));
+        fs::write(&path, "[M2]\n#R B3/S23\n.*$..*$***$\n4 0 0 0 1\n")?;
+        engine.open_pattern(&path)?;
+        let info = engine.info();
+        assert_eq!((info.width, info.height), (20, 10));
+        assert_eq!((info.generation, info.population), (0, 5));
+        assert_eq!(info.rule, "B3/S23");
+        let all = Rect {
+            ix: 0,
+            iy: 0,
+            width: 20,
+            height: 10,
+        };
+        assert_eq!(
+            engine.live_cells(all),
+            [[9, 3], [10, 4], [8, 5], [9, 5], [10, 5]]
+        );
+
+        let path = path.with_extension("cells");
+        let selection = Rect {
+            ix: 8,
+            iy: 3,
+            width: 3,
+            height: 3,
+        };
+        engine.save_pattern(&path, Some(selection))?;
+        assert_eq!(fs::read_to_string(&path)?, ".O.\n..O\nOOO\n");
+        let path = path.with_extension("rle");
+        engine.save_pattern(&path, None)?;
+        let saved = Pattern::from_rle(&fs::read_to_string(&path)?)?;
+        assert_eq!((saved.width, saved.height), (18, 8));
+        assert_eq!(saved.rule.as_deref(), Some("B3/S23"));
+
+        // the world grows for a large pattern.
+        fs::write(&path, "x = 30, y = 1, rule = B36/S23\n30o!\n")?;
+        engine.open_pattern(&path)?;
+        assert_eq!((engine.info().width, engine.info().height), (32, 10));
+        assert_eq!(engine.info().rule, "B36/S23");
+
+        fs::write(&path, "x = 1, y = 1\n2o!\n")?;
+        assert!(engine.open_pattern(&path).is_err());
+        fs::remove_file(&path)?;
+        fs::remove_file(path.with_extension("cells"))?;
+        fs::remove_file(path.with_extension("mc"))?;
+        assert!(engine.open_pattern(&path).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use lifegame_core::{Cell, World, CELL_ALIVE};
+use serde::Serialize;
+
+/// Changes of the world pushed to the webview.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Frame {
+    /// All cells as bit-packed rows.
+    /// Row `iy` takes `ceil(width / 8)` bytes, and the cell `ix` is the bit `ix % 8` of its byte.
+    #[serde(rename_all = "camelCase")]
+    Full {
+        width: usize,
+        height: usize,
+        generation: usize,
+        population: usize,
+        rows: Vec<u8>,
+    },
+    /// Cells changed since the previous frame, as indices `width * iy + ix`
+    #[serde(rename_all = "camelCase")]
+    Delta {
+        generation: usize,
+        population: usize,
+        born: Vec<u32>,
+        died: Vec<u32>,
+    },
+}
+
+/// Encodes the world into the smaller of a full frame and a delta from the previous frame.
+#[derive(Debug, Default)]
+pub struct FrameEncoder {
+    previous: Vec<Cell>,
+    width: usize,
+    height: usize,
+}
+
+impl FrameEncoder {
+    /// Forget the previous frame so that the next frame is full
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+
+    pub fn encode(&mut self, world: &World) -> Frame {
+        let cells = world.present_cells();
+        let (width, height) = (world.nx(), world.ny());
+        let same_size = (width, height) == (self.width, self.height) && !self.previous.is_empty();
+        let mut born = Vec::new();
+        let mut died = Vec::new();
+        if same_size {
+            for (index, (&before, &after)) in self.previous.iter().zip(cells).enumerate() {
+                match (before != CELL_ALIVE, after != CELL_ALIVE) {
+                    (true, false) => born.push(index as u32),
+                    (false, true) => died.push(index as u32),
+                    _ => {}
+                }
+            }
+        }
+        self.previous.clear();
+        self.previous.extend_from_slice(cells);
+        (self.width, self.height) = (width, height);
+
+        // indices take 4 bytes each, and packed rows take a bit per cell.
+        let row_bytes = width.div_ceil(8);
+        if same_size && (born.len() + died.len()) * 4 < row_bytes * height {
+            return Frame::Delta {
+                generation: world.generation(),
+                population: world.population(),
+                born,
+                died,
+            };
+        }
+        let mut rows = vec![0; row_bytes * height];
+        for (index, &cell) in cells.iter().enumerate() {
+            if cell == CELL_ALIVE {
+                let (ix, iy) = (index % width, index / width);
+                rows[row_bytes * iy + ix / 8] |= 1 << (ix % 8);
+            }
+        }
+        Frame::Full {
+            width,
+            height,
+            generation: world.generation(),
+            population: world.population(),
+            rows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lifegame_core::{Pattern, CELL_DEAD};
+
+    #[test]
+    fn test_encode_frames() {
+        let blinker = Pattern::from_rle("x = 3, y = 1\n3o!").unwrap();
+        let mut world = World::new(40, 20, &[CELL_DEAD; 800]).unwrap();
+        world.paste(&blinker, 9, 5);
+        let mut encoder = FrameEncoder::default();
+
+        let Frame::Full { rows, .. } = encoder.encode(&world) else {
+            panic!("the first frame must be full");
+        };
+        assert_eq!(rows.len(), 5 * 20);
+        assert_eq!(rows[5 * 5 + 1], 0b1110);
+
+        world.next();
+        assert_eq!(
+            encoder.encode(&world),
+            Frame::Delta {
+                generation: 1,
+                population: 3,
+                born: vec![40 * 4 + 10, 40 * 6 + 10],
+                died: vec![40 * 5 + 9, 40 * 5 + 11],
+            }
+        );
+
+        // a full frame is smaller when most cells change.
+        let mut soup = World::new(40, 20, &[1; 800]).unwrap();
+        encoder.encode(&soup);
+        soup.next();
+        assert!(matches!(encoder.encode(&soup), Frame::Full { .. }));
+
+        encoder.reset();
+        assert!(matches!(encoder.encode(&soup), Frame::Full { .. }));
+    }
+}
@@ -1,15 +1,236 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod engine;
+mod frame;
+mod recent;
+mod simulation;
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use engine::{Engine, Rect, Transform, WorldConfig, WorldInfo};
+use lifegame_core::Cell;
+use recent::RecentFiles;
+use simulation::{Control, Simulation};
+use tauri::{AppHandle, ClipboardManager, Manager, State};
+
+/// Event pushing a [`frame::Frame`] to the webview
+const FRAME_EVENT: &str = "frame";
+
+/// File of the recent files in the app config directory
+const RECENT_FILES: &str = "recent.json";
+
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
+
+/// Replace the world with a random soup
+#[tauri::command]
+fn create_world(
+    config: WorldConfig,
+    simulation: State<'_, Simulation>,
+) -> Result<WorldInfo, String> {
+    let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+    Ok(simulation.edit(|current| {
+        *current = engine;
+        current.info()
+    }))
+}
+
+#[tauri::command]
+fn world_info(simulation: State<'_, Simulation>) -> WorldInfo {
+    simulation.engine().info()
+}
+
+/// Advance the world by the number of generations
+#[tauri::command]
+fn step(generations: usize, simulation: State<'_, Simulation>) -> WorldInfo {
+    simulation.edit(|engine| {
+        engine.step(generations);
+        engine.info()
+    })
+}
+
+#[tauri::command]
+fn toggle_cell(ix: usize, iy: usize, simulation: State<'_, Simulation>) -> Result<Cell, String> {
+    simulation
+        .edit(|engine| engine.toggle_cell(ix, iy))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_cells(cells: Vec<[usize; 2]>, alive: bool, simulation: State<'_, Simulation>) {
+    simulation.edit(|engine| engine.set_cells(&cells, alive));
+}
+
+/// Draw or erase the cells on a stroke of the mouse
+#[tauri::command]
+fn draw_line(from: [usize; 2], to: [usize; 2], alive: bool, simulation: State<'_, Simulation>) {
+    simulation.edit(|engine| engine.draw_line(from, to, alive));
+}
+
+/// Alive cells in the viewport as `[ix, iy]` pairs
+#[tauri::command]
+fn live_cells(viewport: Rect, simulation: State<'_, Simulation>) -> Vec<[usize; 2]> {
+    simulation.engine().live_cells(viewport)
+}
+
+#[tauri::command]
+fn clear_selection(selection: Rect, simulation: State<'_, Simulation>) {
+    simulation.edit(|engine| engine.clear(selection));
+}
+
+/// Copy the selection to the clipboard as RLE
+#[tauri::command]
+fn copy_selection(
+    selection: Rect,
+    app: AppHandle,
+    simulation: State<'_, Simulation>,
+) -> Result<(), String> {
+    let rle = simulation.engine().copy(selection);
+    app.clipboard_manager()
+        .write_text(rle)
+        .map_err(|e| e.to_string())
+}
+
+/// Paste RLE or plaintext in the clipboard at the cell, returning the pasted rectangle
+#[tauri::command]
+fn paste_clipboard(
+    ix: usize,
+    iy: usize,
+    app: AppHandle,
+    simulation: State<'_, Simulation>,
+) -> Result<Rect, String> {
+    let text = app
+        .clipboard_manager()
+        .read_text()
+        .map_err(|e| e.to_string())?
+        .ok_or("the clipboard has no text.")?;
+    simulation
+        .edit(|engine| engine.paste(&text, ix, iy))
+        .map_err(|e| e.to_string())
+}
+
+/// Rotate or flip the selection, returning the new selection
+#[tauri::command]
+fn transform_selection(
+    selection: Rect,
+    transform: Transform,
+    simulation: State<'_, Simulation>,
+) -> Rect {
+    simulation.edit(|engine| engine.transform(selection, transform))
+}
+
+/// Replace the world with the pattern file, and add it to the recent files
+#[tauri::command]
+fn open_pattern(
+    path: PathBuf,
+    simulation: State<'_, Simulation>,
+    recent: State<'_, Mutex<RecentFiles>>,
+) -> Result<WorldInfo, String> {
+    let info = simulation
+        .edit(|engine| {
+            engine.open_pattern(&path)?;
+            anyhow::Ok(engine.info())
+        })
+        .map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+    recent
+        .lock()
+        .unwrap()
+        .push(&path)
+        .map_err(|e| e.to_string())?;
+    Ok(info)
+}
+
+/// Save the selection or the whole world, and add the file to the recent files
+#[tauri::command]
+fn save_pattern(
+    path: PathBuf,
+    selection: Option<Rect>,
+    simulation: State<'_, Simulation>,
+    recent: State<'_, Mutex<RecentFiles>>,
+) -> Result<(), String> {
+    simulation
+        .engine()
+        .save_pattern(&path, selection)
+        .map_err(|e| format!("cannot save {}: {}", path.display(), e))?;
+    recent
+        .lock()
+        .unwrap()
+        .push(&path)
+        .map_err(|e| e.to_string())
+}
+
+/// Pattern files opened or saved recently, the most recent first
+#[tauri::command]
+fn recent_files(recent: State<'_, Mutex<RecentFiles>>) -> Vec<PathBuf> {
+    recent.lock().unwrap().paths().to_vec()
+}
+
+#[tauri::command]
+fn run(simulation: State<'_, Simulation>) -> Control {
+    simulation.run()
+}
+
+#[tauri::command]
+fn pause(simulation: State<'_, Simulation>) -> Control {
+    simulation.pause()
+}
+
+/// Set the generations per second
+#[tauri::command]
+fn set_speed(speed: f64, simulation: State<'_, Simulation>) -> Control {
+    simulation.set_speed(speed)
+}
+
+#[tauri::command]
+fn control(simulation: State<'_, Simulation>) -> Control {
+    simulation.control()
+}
+
+/// Push a full frame to the webview which has just started listening
 #[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+fn resync(simulation: State<'_, Simulation>) {
+    simulation.resync();
 }
 
 fn main() {
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![greet])
+        .manage(Simulation::default())
+        .setup(|app| {
+            let recent = match app.path_resolver().app_config_dir() {
+                Some(dir) => RecentFiles::load(&dir.join(RECENT_FILES)),
+                None => RecentFiles::default(),
+            };
+            app.manage(Mutex::new(recent));
+            let handle = app.handle();
+            app.state::<Simulation>().start(move |frame| {
+                if let Err(e) = handle.emit_all(FRAME_EVENT, frame) {
+                    eprintln!("cannot push a frame: {}", e);
+                }
+            });
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            create_world,
+            world_info,
+            step,
+            toggle_cell,
+            set_cells,
+            draw_line,
+            live_cells,
+            clear_selection,
+            copy_selection,
+            paste_clipboard,
+            transform_selection,
+            open_pattern,
+            save_pattern,
+            recent_files,
+            run,
+            pause,
+            set_speed,
+            control,
+            resync
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+/// Maximum number of recent files
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// Pattern files opened or saved recently, persisted as JSON.
+#[derive(Debug, Default)]
+pub struct RecentFiles {
+    /// file of the list, not persisted if none
+    file: Option<PathBuf>,
+    /// the most recent first
+    paths: Vec<PathBuf>,
+}
+
+impl RecentFiles {
+    /// Load the list from the file, which is empty if the file is missing or broken
+    pub fn load(file: &Path) -> RecentFiles {
+        let paths = fs::read_to_string(file)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        RecentFiles {
+            file: Some(file.to_path_buf()),
+            paths,
+        }
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Move the path to the front and save the list
+    pub fn push(&mut self, path: &Path) -> Result<()> {
+        self.paths.retain(|p| p != path);
+        self.paths.insert(0, path.to_path_buf());
+        self.paths.truncate(MAX_RECENT_FILES);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, serde_json::to_string_pretty(&self.paths)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_files() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("lifegame-gui-recent-{}", std::process::id()));
+        let file = dir.join("config").join("recent.json");
+        let mut recent = RecentFiles::load(&file);
+        assert!(recent.paths().is_empty());
+
+        for i in 0..MAX_RECENT_FILES + 2 {
+            recent.push(Path::new(&format!("{}.rle", i)))?;
+        }
+        recent.push(Path::new("3.rle"))?;
+        let loaded = RecentFiles::load(&file);
+        assert_eq!(loaded.paths().len(), MAX_RECENT_FILES);
+        assert_eq!(loaded.paths()[0], Path::new("3.rle"));
+        assert_eq!(loaded.paths()[1], Path::new("11.rle"));
+        assert_eq!(
+            loaded
+                .paths()
+                .iter()
+                .filter(|p| p.ends_with("3.rle"))
+                .count(),
+            1
+        );
+
+        fs::write(&file, "broken")?;
+        assert!(RecentFiles::load(&file).paths().is_empty());
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
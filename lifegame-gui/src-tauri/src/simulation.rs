@@ -0,0 +1,231 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::engine::Engine;
+use crate::frame::{Frame, FrameEncoder};
+
+/// Interval of frames pushed to the webview, about the display rate
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Maximum generations per second
+pub const MAX_SPEED: f64 = 10_000.0;
+
+/// Run state of the simulation sent to the webview.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Control {
+    pub running: bool,
+    /// generations per second
+    pub speed: f64,
+}
+
+/// Frames due regardless of stepping.
+#[derive(Debug, Default)]
+struct Pending {
+    /// the world is edited, and a frame is due even while paused
+    frame: bool,
+    /// the webview lost the previous frames, and the next frame must be full
+    full: bool,
+}
+
+#[derive(Debug)]
+struct Shared {
+    engine: Mutex<Engine>,
+    control: Mutex<Control>,
+    pending: Mutex<Pending>,
+}
+
+/// The world evolving on a background thread, which pushes a frame whenever the world changes.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    shared: Arc<Shared>,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new(Engine::default())
+    }
+}
+
+impl Simulation {
+    pub fn new(engine: Engine) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                engine: Mutex::new(engine),
+                control: Mutex::new(Control {
+                    running: false,
+                    speed: 30.0,
+                }),
+                pending: Mutex::new(Pending {
+                    frame: true,
+                    full: true,
+                }),
+            }),
+        }
+    }
+
+    /// Start the simulation thread calling `emit` with every frame
+    pub fn start<F>(&self, mut emit: F) -> thread::JoinHandle<()>
+    where
+        F: FnMut(Frame) + Send + 'static,
+    {
+        let shared = Arc::clone(&self.shared);
+        thread::spawn(move || {
+            let mut encoder = FrameEncoder::default();
+            // generations due but not stepped yet
+            let mut due = 0.0;
+            let mut last = Instant::now();
+            loop {
+                thread::sleep(FRAME_INTERVAL);
+                let elapsed = last.elapsed().as_secs_f64();
+                last = Instant::now();
+                let control = *shared.control.lock().unwrap();
+                let pending = std::mem::take(&mut *shared.pending.lock().unwrap());
+                if pending.full {
+                    encoder.reset();
+                }
+                let changed = pending.frame;
+                // the engine is released before emitting, so that edits do not wait for the webview
+                let frame = {
+                    let mut engine = shared.engine.lock().unwrap();
+                    let generations = match control.running {
+                        true => take_due(&mut due, control.speed, elapsed),
+                        false => {
+                            due = 0.0;
+                            0
+                        }
+                    };
+                    if generations > 0 {
+                        engine.step(generations);
+                    }
+                    (changed || generations > 0).then(|| encoder.encode(engine.world()))
+                };
+                if let Some(frame) = frame {
+                    emit(frame);
+                }
+            }
+        })
+    }
+
+    /// Edit the engine, pushing a frame afterwards
+    pub fn edit<R>(&self, f: impl FnOnce(&mut Engine) -> R) -> R {
+        let result = f(&mut self.shared.engine.lock().unwrap());
+        self.shared.pending.lock().unwrap().frame = true;
+        result
+    }
+
+    /// Push a full frame, for a webview which has just started listening
+    pub fn resync(&self) {
+        *self.shared.pending.lock().unwrap() = Pending {
+            frame: true,
+            full: true,
+        };
+    }
+
+    /// Read the engine
+    pub fn engine(&self) -> MutexGuard<'_, Engine> {
+        self.shared.engine.lock().unwrap()
+    }
+
+    pub fn control(&self) -> Control {
+        *self.shared.control.lock().unwrap()
+    }
+
+    pub fn run(&self) -> Control {
+        self.update_control(|control| control.running = true)
+    }
+
+    pub fn pause(&self) -> Control {
+        self.update_control(|control| control.running = false)
+    }
+
+    /// Set the generations per second, clamped to `1..=MAX_SPEED`
+    pub fn set_speed(&self, speed: f64) -> Control {
+        self.update_control(|control| control.speed = speed.clamp(1.0, MAX_SPEED))
+    }
+
+    fn update_control(&self, f: impl FnOnce(&mut Control)) -> Control {
+        let mut control = self.shared.control.lock().unwrap();
+        f(&mut control);
+        *control
+    }
+}
+
+/// Take the whole generations due after `elapsed` seconds at `speed` generations per second.
+///
+/// The generations due are capped by two frames, or a generation at low speeds, so that the
+/// simulation slows down, rather than falling further behind, when stepping takes longer than a
+/// frame.
+fn take_due(due: &mut f64, speed: f64, elapsed: f64) -> usize {
+    let cap = (speed * FRAME_INTERVAL.as_secs_f64() * 2.0).max(1.0);
+    *due = (*due + speed * elapsed).min(cap);
+    let generations = due.floor();
+    *due -= generations;
+    generations as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::WorldConfig;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_take_due() {
+        let mut due = 0.0;
+        assert_eq!(take_due(&mut due, 1000.0, FRAME_INTERVAL.as_secs_f64()), 16);
+        assert_eq!(take_due(&mut due, 30.0, 0.02), 0);
+        assert_eq!(take_due(&mut due, 30.0, 0.02), 1);
+
+        // a step slower than the frames does not make the backlog grow.
+        let mut due = 0.0;
+        let taken: Vec<_> = (0..4).map(|_| take_due(&mut due, 1000.0, 1.0)).collect();
+        assert_eq!(taken, [32; 4]);
+        assert!(due < 1.0);
+    }
+
+    #[test]
+    fn test_simulation_thread() {
+        let engine = Engine::new(&WorldConfig {
+            seed: Some(1),
+            ..WorldConfig::default()
+        })
+        .unwrap();
+        let simulation = Simulation::new(engine);
+        let (sender, receiver) = mpsc::channel();
+        simulation.start(move |frame| {
+            let _ = sender.send(frame);
+        });
+        let timeout = Duration::from_secs(5);
+
+        // the first frame is full, even while paused.
+        let frame = receiver.recv_timeout(timeout).unwrap();
+        assert!(matches!(frame, Frame::Full { generation: 0, .. }));
+
+        simulation.set_speed(MAX_SPEED);
+        simulation.run();
+        let frame = receiver.recv_timeout(timeout).unwrap();
+        let generation = match frame {
+            Frame::Full { generation, .. } | Frame::Delta { generation, .. } => generation,
+        };
+        assert!(generation > 0);
+
+        simulation.pause();
+        while receiver.recv_timeout(FRAME_INTERVAL * 4).is_ok() {}
+        let generation = simulation.engine().info().generation;
+        simulation.edit(|engine| engine.set_cells(&[[1, 1]], true));
+        match receiver.recv_timeout(timeout).unwrap() {
+            Frame::Full { generation: g, .. } | Frame::Delta { generation: g, .. } => {
+                assert_eq!(g, generation)
+            }
+        }
+        assert_eq!(simulation.control().speed, MAX_SPEED);
+
+        simulation.resync();
+        let frame = receiver.recv_timeout(timeout).unwrap();
+        assert!(matches!(frame, Frame::Full { .. }));
+    }
+}
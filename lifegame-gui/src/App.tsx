@@ -1,47 +1,440 @@
-import { useState } from "react";
-import { invoke } from "@tauri-apps/api/tauri";
+import { useCallback, useEffect, useRef, useState } from "react";
 import "./App.css";
 import Canvas from "./Canvas";
+import {
+  CellIndex,
+  Control,
+  Rect,
+  Transform,
+  WorldConfig,
+  WorldInfo,
+  chooseOpenPath,
+  chooseSavePath,
+  clearSelection,
+  copySelection,
+  createWorld,
+  control,
+  drawLine,
+  listenFileDrop,
+  listenFrames,
+  openPattern,
+  pasteClipboard,
+  pause,
+  recentFiles,
+  run,
+  savePattern,
+  setSpeed,
+  step,
+  transformSelection,
+  worldInfo,
+} from "./lifegame";
+import { View } from "./view";
+import { WorldBuffer } from "./world";
+
+const CANVAS_WIDTH = 800
+const CANVAS_HEIGHT = 560
+
+/** Zoom factor per wheel notch */
+const ZOOM_STEP = 1.2
+/** Minimum pixels per cell to draw the grid */
+const GRID_SCALE = 4
+
+const ALIVE_COLOR = '#2050d0'
+const DEAD_COLOR = '#ffffff'
+const OUTSIDE_COLOR = '#c8c8c8'
+const GRID_COLOR = '#e0e0e0'
+const SELECTION_COLOR = '#d03030'
+
+type Tool = "draw" | "erase" | "select"
+
+/** Mouse drag in progress on the canvas */
+type Drag =
+  | { type: "pan", px: number, py: number }
+  | { type: "draw", alive: boolean, last: CellIndex }
+  | { type: "select", start: CellIndex }
 
 function App() {
-  const [greetMsg, setGreetMsg] = useState("");
-  const [name, setName] = useState("");
+  const [info, setInfo] = useState<WorldInfo | null>(null)
+  const [config, setConfig] = useState<WorldConfig>({ width: 120, height: 80, density: 0.2, rule: "B3/S23" })
+  const [seed, setSeed] = useState("")
+  const [generations, setGenerations] = useState(1)
+  const [state, setState] = useState<Control>({ running: false, speed: 30 })
+  const [status, setStatus] = useState({ generation: 0, population: 0 })
+  const [tool, setTool] = useState<Tool>("draw")
+  const [selection, setSelection] = useState<Rect | null>(null)
+  const [recent, setRecent] = useState<string[]>([])
+  const [error, setError] = useState("")
+  // cells mirrored from the frames pushed by the backend
+  const world = useRef(new WorldBuffer())
+  const view = useRef(new View())
+  const drag = useRef<Drag | null>(null)
+  // cell under the mouse, where the clipboard is pasted without a selection
+  const hovered = useRef<CellIndex>([0, 0])
+  // the selection read by the draw function, which is created once
+  const selected = useRef<Rect | null>(null)
+  // the view or the selection is changed since the last drawing
+  const dirty = useRef(true)
+  // version of the world and canvas size drawn last, as resizing the canvas clears it
+  const drawn = useRef("")
+
+  async function call<T>(command: () => Promise<T>): Promise<T | undefined> {
+    try {
+      const result = await command()
+      setError("")
+      return result
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  function fit(info: WorldInfo) {
+    view.current.fit(info.width, info.height, CANVAS_WIDTH, CANVAS_HEIGHT)
+    dirty.current = true
+  }
+
+  function select(rect: Rect | null) {
+    selected.current = rect
+    dirty.current = true
+    setSelection(rect)
+  }
+
+  useEffect(() => {
+    call(worldInfo).then((info) => {
+      if (info) {
+        setInfo(info)
+        fit(info)
+      }
+    })
+    call(control).then((control) => control && setState(control))
+    call(recentFiles).then((paths) => paths && setRecent(paths))
+    const unlistenFrames = listenFrames((frame) => world.current.apply(frame))
+    const unlistenFileDrop = listenFileDrop((paths) => paths.length > 0 && onOpen(paths[0]))
+    return () => {
+      unlistenFrames.then((f) => f())
+      unlistenFileDrop.then((f) => f())
+    }
+  }, [])
+
+  const draw = useCallback((context: CanvasRenderingContext2D) => {
+    const buffer = world.current
+    const { width, height } = context.canvas
+    const key = `${buffer.version}:${width}x${height}`
+    if (key === drawn.current && !dirty.current) {
+      return
+    }
+    drawn.current = key
+    dirty.current = false
+    setStatus({ generation: buffer.generation, population: buffer.population })
+
+    const { scale } = view.current
+    context.fillStyle = OUTSIDE_COLOR
+    context.fillRect(0, 0, width, height)
+    const [left, top] = view.current.toPixel(0, 0)
+    context.fillStyle = DEAD_COLOR
+    context.fillRect(left, top, buffer.width * scale, buffer.height * scale)
+
+    // only the cells in the canvas
+    const [x0, y0] = view.current.toCell(0, 0)
+    const [x1, y1] = view.current.toCell(width, height)
+    const ix0 = Math.max(Math.floor(x0), 0)
+    const iy0 = Math.max(Math.floor(y0), 0)
+    const ix1 = Math.min(Math.ceil(x1), buffer.width)
+    const iy1 = Math.min(Math.ceil(y1), buffer.height)
+    context.fillStyle = ALIVE_COLOR
+    for (let iy = iy0; iy < iy1; iy++) {
+      for (let ix = ix0; ix < ix1; ix++) {
+        if (buffer.isAlive(ix, iy)) {
+          const [px, py] = view.current.toPixel(ix, iy)
+          context.fillRect(px, py, scale, scale)
+        }
+      }
+    }
+
+    if (scale >= GRID_SCALE) {
+      context.strokeStyle = GRID_COLOR
+      context.lineWidth = 1
+      context.beginPath()
+      for (let ix = ix0; ix <= ix1; ix++) {
+        const [px] = view.current.toPixel(ix, 0)
+        context.moveTo(Math.round(px) + 0.5, Math.max(top, 0))
+        context.lineTo(Math.round(px) + 0.5, Math.min(top + buffer.height * scale, height))
+      }
+      for (let iy = iy0; iy <= iy1; iy++) {
+        const [, py] = view.current.toPixel(0, iy)
+        context.moveTo(Math.max(left, 0), Math.round(py) + 0.5)
+        context.lineTo(Math.min(left + buffer.width * scale, width), Math.round(py) + 0.5)
+      }
+      context.stroke()
+    }
+
+    const rect = selected.current
+    if (rect) {
+      const [px, py] = view.current.toPixel(rect.ix, rect.iy)
+      context.strokeStyle = SELECTION_COLOR
+      context.lineWidth = 2
+      context.setLineDash([4, 4])
+      context.strokeRect(px, py, rect.width * scale, rect.height * scale)
+      context.setLineDash([])
+    }
+  }, [])
+
+  /** Cell at the mouse, clamped to the world */
+  function cellAt(event: React.MouseEvent<HTMLCanvasElement>): CellIndex {
+    const rect = event.currentTarget.getBoundingClientRect()
+    const [x, y] = view.current.toCell(event.clientX - rect.left, event.clientY - rect.top)
+    const clamp = (n: number, size: number) => Math.min(Math.max(Math.floor(n), 0), Math.max(size - 1, 0))
+    return [clamp(x, world.current.width), clamp(y, world.current.height)]
+  }
+
+  function selectionBetween([ax, ay]: CellIndex, [bx, by]: CellIndex): Rect {
+    return {
+      ix: Math.min(ax, bx),
+      iy: Math.min(ay, by),
+      width: Math.abs(ax - bx) + 1,
+      height: Math.abs(ay - by) + 1,
+    }
+  }
+
+  function onMouseDown(event: React.MouseEvent<HTMLCanvasElement>) {
+    if (event.button !== 0) {
+      // middle or right button pans
+      drag.current = { type: "pan", px: event.clientX, py: event.clientY }
+      return
+    }
+    const cell = cellAt(event)
+    switch (tool) {
+      case "draw":
+      case "erase": {
+        const alive = tool === "draw"
+        drag.current = { type: "draw", alive, last: cell }
+        call(() => drawLine(cell, cell, alive))
+        break
+      }
+      case "select":
+        drag.current = { type: "select", start: cell }
+        select(selectionBetween(cell, cell))
+        break
+    }
+  }
+
+  function onMouseMove(event: React.MouseEvent<HTMLCanvasElement>) {
+    const cell = cellAt(event)
+    hovered.current = cell
+    const current = drag.current
+    if (!current) {
+      return
+    }
+    switch (current.type) {
+      case "pan":
+        view.current.panBy(event.clientX - current.px, event.clientY - current.py)
+        current.px = event.clientX
+        current.py = event.clientY
+        dirty.current = true
+        break
+      case "draw":
+        if (cell[0] !== current.last[0] || cell[1] !== current.last[1]) {
+          const { alive, last } = current
+          current.last = cell
+          call(() => drawLine(last, cell, alive))
+        }
+        break
+      case "select":
+        select(selectionBetween(current.start, cell))
+        break
+    }
+  }
+
+  function onWheel(event: React.WheelEvent<HTMLCanvasElement>) {
+    const rect = event.currentTarget.getBoundingClientRect()
+    const factor = event.deltaY < 0 ? ZOOM_STEP : 1 / ZOOM_STEP
+    view.current.zoomAt(event.clientX - rect.left, event.clientY - rect.top, factor)
+    dirty.current = true
+  }
+
+  async function onTransform(transform: Transform) {
+    if (selection) {
+      const rect = await call(() => transformSelection(selection, transform))
+      rect && select(rect)
+    }
+  }
+
+  async function onPaste() {
+    const [ix, iy] = selection ? [selection.ix, selection.iy] : hovered.current
+    const rect = await call(() => pasteClipboard(ix, iy))
+    rect && select(rect)
+  }
+
+  function onClear() {
+    if (selection) {
+      call(() => clearSelection(selection))
+    }
+  }
+
+  function onCopy() {
+    if (selection) {
+      call(() => copySelection(selection))
+    }
+  }
+
+  function onKeyDown(event: React.KeyboardEvent) {
+    if (event.target instanceof HTMLInputElement) {
+      return
+    }
+    const command = event.ctrlKey || event.metaKey
+    switch (event.key.toLowerCase()) {
+      case "c":
+        command && onCopy()
+        break
+      case "v":
+        command ? onPaste() : onTransform("flipVertically")
+        break
+      case "h":
+        onTransform("flipHorizontally")
+        break
+      case "r":
+        onTransform(event.shiftKey ? "rotateCounterclockwise" : "rotateClockwise")
+        break
+      case "delete":
+      case "backspace":
+        onClear()
+        break
+      case "escape":
+        select(null)
+        break
+      default:
+        return
+    }
+    event.preventDefault()
+  }
+
+  async function onCreate(event: React.FormEvent) {
+    event.preventDefault()
+    const parsedSeed = seed.trim() === "" ? undefined : Number(seed)
+    const info = await call(() => createWorld({ ...config, seed: parsedSeed }))
+    if (info) {
+      setInfo(info)
+      select(null)
+      fit(info)
+    }
+  }
+
+  async function onOpen(path: string | null) {
+    if (!path) {
+      return
+    }
+    const info = await call(() => openPattern(path))
+    if (info) {
+      setInfo(info)
+      select(null)
+      fit(info)
+    }
+    call(recentFiles).then((paths) => paths && setRecent(paths))
+  }
+
+  async function onSave() {
+    const path = await call(chooseSavePath)
+    if (path) {
+      await call(() => savePattern(path, selection))
+      call(recentFiles).then((paths) => paths && setRecent(paths))
+    }
+  }
 
-  async function greet() {
-    // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
-    setGreetMsg(await invoke("greet", { name }));
+  async function onRunOrPause() {
+    const control = await call(state.running ? pause : run)
+    if (control) {
+      setState(control)
+    }
   }
 
-  function draw(context: CanvasRenderingContext2D, frameCount: number) {
-    context.clearRect(0, 0, context.canvas.width, context.canvas.height)
-    context.fillStyle = '#000000'
-    context.beginPath()
-    context.arc(50, 100, 20 * Math.sin(frameCount * 0.05) ** 2, 0, 2 * Math.PI)
-    context.fill()
+  async function onSpeed(speed: number) {
+    const control = await call(() => setSpeed(speed))
+    if (control) {
+      setState(control)
+    }
   }
 
   return (
-    <div className="container">
-      <p>Click on the Tauri, Vite, and React logos to learn more.</p>
-
-      <form
-        className="row"
-        onSubmit={(e) => {
-          e.preventDefault();
-          greet();
-        }}
-      >
-        <input
-          id="greet-input"
-          onChange={(e) => setName(e.currentTarget.value)}
-          placeholder="Enter a name..."
-        />
-        <button type="submit">Greet</button>
+    <div className="container" tabIndex={0} onKeyDown={onKeyDown}>
+      <form className="row" onSubmit={onCreate}>
+        <label>
+          width
+          <input type="number" min={3} value={config.width}
+            onChange={(e) => setConfig({ ...config, width: Number(e.currentTarget.value) })} />
+        </label>
+        <label>
+          height
+          <input type="number" min={3} value={config.height}
+            onChange={(e) => setConfig({ ...config, height: Number(e.currentTarget.value) })} />
+        </label>
+        <label>
+          density
+          <input type="number" min={0} max={1} step={0.05} value={config.density}
+            onChange={(e) => setConfig({ ...config, density: Number(e.currentTarget.value) })} />
+        </label>
+        <label>
+          seed
+          <input value={seed} placeholder="random" onChange={(e) => setSeed(e.currentTarget.value)} />
+        </label>
+        <label>
+          rule
+          <input value={config.rule} onChange={(e) => setConfig({ ...config, rule: e.currentTarget.value })} />
+        </label>
+        <button type="submit">Create</button>
       </form>
 
-      <p>{greetMsg}</p>
+      <div className="row">
+        <button onClick={async () => onOpen(await call(chooseOpenPath) ?? null)}>Open</button>
+        <button onClick={onSave} title={selection ? "save the selection" : "save the whole world"}>Save</button>
+        <select value="" disabled={recent.length === 0} onChange={(e) => onOpen(e.currentTarget.value)}>
+          <option value="" disabled>recent files</option>
+          {recent.map((path) => <option key={path} value={path}>{path}</option>)}
+        </select>
+      </div>
+
+      <div className="row">
+        <button onClick={onRunOrPause}>{state.running ? "Pause" : "Run"}</button>
+        <label>
+          gen/s
+          <input type="number" min={1} max={10000} value={state.speed}
+            onChange={(e) => onSpeed(Number(e.currentTarget.value))} />
+        </label>
+        <input type="number" min={1} value={generations}
+          onChange={(e) => setGenerations(Number(e.currentTarget.value))} />
+        <button disabled={state.running} onClick={() => call(() => step(generations))}>Step</button>
+      </div>
 
-      <Canvas draw={draw} />
+      <div className="row">
+        {(["draw", "erase", "select"] as Tool[]).map((t) => (
+          <button key={t} className={t === tool ? "active" : ""} onClick={() => setTool(t)}>{t}</button>
+        ))}
+        <button disabled={!selection} onClick={() => onTransform("rotateClockwise")} title="R">rotate</button>
+        <button disabled={!selection} onClick={() => onTransform("flipHorizontally")} title="H">flip H</button>
+        <button disabled={!selection} onClick={() => onTransform("flipVertically")} title="V">flip V</button>
+        <button disabled={!selection} onClick={onClear} title="Delete">clear</button>
+        <button disabled={!selection} onClick={onCopy} title="Ctrl+C">copy</button>
+        <button onClick={onPaste} title="Ctrl+V">paste</button>
+        <button onClick={() => info && fit(info)}>fit</button>
+      </div>
+
+      <p>
+        {info && `gen=${status.generation} population=${status.population} rule=${info.rule} seed=${info.seed}`}
+        {selection && ` selection=${selection.width}x${selection.height}`}
+      </p>
+      {error && <p className="error">{error}</p>}
+
+      {info && (
+        <Canvas
+          draw={draw}
+          width={CANVAS_WIDTH}
+          height={CANVAS_HEIGHT}
+          onMouseDown={onMouseDown}
+          onMouseMove={onMouseMove}
+          onMouseUp={() => drag.current = null}
+          onMouseLeave={() => drag.current = null}
+          onWheel={onWheel}
+          onContextMenu={(e) => e.preventDefault()}
+        />
+      )}
     </div>
   );
 }
@@ -0,0 +1,174 @@
+import { open, save } from "@tauri-apps/api/dialog";
+import { UnlistenFn, listen } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/tauri";
+import { appWindow } from "@tauri-apps/api/window";
+
+export interface WorldConfig {
+  width: number
+  height: number
+  density: number
+  seed?: number
+  rule?: string
+}
+
+export interface WorldInfo {
+  width: number
+  height: number
+  generation: number
+  population: number
+  rule: string
+  seed: number
+}
+
+/** Rectangle of cells, a viewport or a selection */
+export interface Rect {
+  ix: number
+  iy: number
+  width: number
+  height: number
+}
+
+export type Transform = "rotateClockwise" | "rotateCounterclockwise" | "flipHorizontally" | "flipVertically"
+
+/** Cell index as [ix, iy] */
+export type CellIndex = [number, number]
+
+export function createWorld(config: WorldConfig): Promise<WorldInfo> {
+  return invoke("create_world", { config })
+}
+
+export function worldInfo(): Promise<WorldInfo> {
+  return invoke("world_info")
+}
+
+export function step(generations: number): Promise<WorldInfo> {
+  return invoke("step", { generations })
+}
+
+export function toggleCell(ix: number, iy: number): Promise<number> {
+  return invoke("toggle_cell", { ix, iy })
+}
+
+export function setCells(cells: CellIndex[], alive: boolean): Promise<void> {
+  return invoke("set_cells", { cells, alive })
+}
+
+/** Draw or erase the cells on the line segment */
+export function drawLine(from: CellIndex, to: CellIndex, alive: boolean): Promise<void> {
+  return invoke("draw_line", { from, to, alive })
+}
+
+export function liveCells(viewport: Rect): Promise<CellIndex[]> {
+  return invoke("live_cells", { viewport })
+}
+
+export function clearSelection(selection: Rect): Promise<void> {
+  return invoke("clear_selection", { selection })
+}
+
+/** Copy the selection to the clipboard as RLE */
+export function copySelection(selection: Rect): Promise<void> {
+  return invoke("copy_selection", { selection })
+}
+
+/** Paste the pattern in the clipboard at the cell, resolving the pasted rectangle */
+export function pasteClipboard(ix: number, iy: number): Promise<Rect> {
+  return invoke("paste_clipboard", { ix, iy })
+}
+
+/** Rotate or flip the selection, resolving the new selection */
+export function transformSelection(selection: Rect, transform: Transform): Promise<Rect> {
+  return invoke("transform_selection", { selection, transform })
+}
+
+/** Pattern files read by the backend */
+const PATTERN_FILTERS = [
+  { name: "Patterns", extensions: ["rle", "cells", "txt", "mc"] },
+  { name: "All files", extensions: ["*"] },
+]
+
+/** Replace the world with the pattern file, detecting RLE, plaintext or macrocell */
+export function openPattern(path: string): Promise<WorldInfo> {
+  return invoke("open_pattern", { path })
+}
+
+/** Save the selection or the whole world, as plaintext for `.cells` and `.txt` and RLE otherwise */
+export function savePattern(path: string, selection: Rect | null): Promise<void> {
+  return invoke("save_pattern", { path, selection })
+}
+
+/** Pattern files opened or saved recently, the most recent first */
+export function recentFiles(): Promise<string[]> {
+  return invoke("recent_files")
+}
+
+/** Ask a pattern file to open, resolving null if cancelled */
+export async function chooseOpenPath(): Promise<string | null> {
+  const path = await open({ filters: PATTERN_FILTERS })
+  return Array.isArray(path) ? path[0] ?? null : path
+}
+
+/** Ask a pattern file to save, resolving null if cancelled */
+export function chooseSavePath(): Promise<string | null> {
+  return save({ filters: PATTERN_FILTERS.slice(0, 1), defaultPath: "pattern.rle" })
+}
+
+/** Listen to files dropped onto the window */
+export function listenFileDrop(handler: (paths: string[]) => void): Promise<UnlistenFn> {
+  return appWindow.onFileDropEvent((event) => {
+    if (event.payload.type === "drop") {
+      handler(event.payload.paths)
+    }
+  })
+}
+
+/** Run state of the simulation thread */
+export interface Control {
+  running: boolean
+  /** generations per second */
+  speed: number
+}
+
+/** Changes of the world pushed by the simulation thread */
+export type Frame =
+  | {
+    type: "full"
+    width: number
+    height: number
+    generation: number
+    population: number
+    /** bit-packed rows, ceil(width / 8) bytes per row, cell ix at bit ix % 8 */
+    rows: number[]
+  }
+  | {
+    type: "delta"
+    generation: number
+    population: number
+    /** indices width * iy + ix of cells born since the previous frame */
+    born: number[]
+    /** indices of cells died since the previous frame */
+    died: number[]
+  }
+
+export function run(): Promise<Control> {
+  return invoke("run")
+}
+
+export function pause(): Promise<Control> {
+  return invoke("pause")
+}
+
+export function setSpeed(speed: number): Promise<Control> {
+  return invoke("set_speed", { speed })
+}
+
+export function control(): Promise<Control> {
+  return invoke("control")
+}
+
+/** Listen to the frames, starting with a full frame */
+export async function listenFrames(handler: (frame: Frame) => void): Promise<UnlistenFn> {
+  const unlisten = await listen<Frame>("frame", (event) => handler(event.payload))
+  await invoke("resync")
+  return unlisten
+}
@@ -0,0 +1,44 @@
+/** Minimum pixels per cell */
+export const MIN_SCALE = 0.5
+/** Maximum pixels per cell */
+export const MAX_SCALE = 64
+
+/** Mapping between canvas pixels and cells, zoomed and panned by the mouse */
+export class View {
+  /** pixels per cell */
+  scale = 6
+  /** cell coordinates at the top-left corner of the canvas */
+  x = 0
+  y = 0
+
+  /** Cell coordinates at the pixel, not rounded */
+  toCell(px: number, py: number): [number, number] {
+    return [this.x + px / this.scale, this.y + py / this.scale]
+  }
+
+  /** Pixel coordinates of the top-left corner of the cell */
+  toPixel(ix: number, iy: number): [number, number] {
+    return [(ix - this.x) * this.scale, (iy - this.y) * this.scale]
+  }
+
+  /** Zoom by the factor keeping the cell at the pixel in place */
+  zoomAt(px: number, py: number, factor: number) {
+    const [cx, cy] = this.toCell(px, py)
+    this.scale = Math.min(Math.max(this.scale * factor, MIN_SCALE), MAX_SCALE)
+    this.x = cx - px / this.scale
+    this.y = cy - py / this.scale
+  }
+
+  /** Move the cells by the pixels */
+  panBy(dx: number, dy: number) {
+    this.x -= dx / this.scale
+    this.y -= dy / this.scale
+  }
+
+  /** Show the whole world of the size in the canvas */
+  fit(width: number, height: number, canvasWidth: number, canvasHeight: number) {
+    this.scale = Math.min(Math.max(Math.min(canvasWidth / width, canvasHeight / height), MIN_SCALE), MAX_SCALE)
+    this.x = (width - canvasWidth / this.scale) / 2
+    this.y = (height - canvasHeight / this.scale) / 2
+  }
+}
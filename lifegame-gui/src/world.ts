@@ -0,0 +1,52 @@
+import { Frame } from "./lifegame";
+
+/** Cells of the world mirrored from the frames pushed by the backend */
+export class WorldBuffer {
+  width = 0
+  height = 0
+  generation = 0
+  population = 0
+  /** 1 for alive cells, row by row */
+  cells = new Uint8Array(0)
+  /** bumped whenever the cells change, so that the canvas redraws only then */
+  version = 0
+
+  apply(frame: Frame) {
+    switch (frame.type) {
+      case "full": {
+        const { width, height, rows } = frame
+        const rowBytes = Math.ceil(width / 8)
+        if (width !== this.width || height !== this.height) {
+          this.width = width
+          this.height = height
+          this.cells = new Uint8Array(width * height)
+        }
+        for (let iy = 0; iy < height; iy++) {
+          for (let ix = 0; ix < width; ix++) {
+            this.cells[width * iy + ix] = (rows[rowBytes * iy + (ix >> 3)] >> (ix & 7)) & 1
+          }
+        }
+        break
+      }
+      case "delta":
+        if (this.cells.length === 0) {
+          // a delta before the first full frame cannot be applied.
+          return
+        }
+        for (const index of frame.born) {
+          this.cells[index] = 1
+        }
+        for (const index of frame.died) {
+          this.cells[index] = 0
+        }
+        break
+    }
+    this.generation = frame.generation
+    this.population = frame.population
+    this.version++
+  }
+
+  isAlive(ix: number, iy: number): boolean {
+    return this.cells[this.width * iy + ix] === 1
+  }
+}
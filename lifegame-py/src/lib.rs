@@ -0,0 +1,159 @@
+//! Python binding of lifegame-core for analysis notebooks.
+//!
+//! Build with `maturin develop` in this directory.
+
+use lifegame_core::{Pattern, Rule, World, CELL_ALIVE, CELL_DEAD};
+use numpy::ndarray::Array2;
+use numpy::{
+    IntoPyArray, PyArray2, PyArrayDescrMethods, PyReadonlyArray2, PyUntypedArray,
+    PyUntypedArrayMethods,
+};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::pyclass::CompareOp;
+
+/// Game of life world surrounded by always-dead cells.
+///
+/// Arrays are indexed as `[iy, ix]` and exclude the surrounding dead cells.
+#[pyclass(name = "World", module = "lifegame")]
+#[derive(Clone)]
+struct PyWorld {
+    world: World,
+}
+
+#[pymethods]
+impl PyWorld {
+    /// Create a world of a 2D array, whose nonzero or true elements are alive
+    #[new]
+    #[pyo3(signature = (cells, rule = None))]
+    fn new(cells: &Bound<'_, PyAny>, rule: Option<&str>) -> PyResult<Self> {
+        let invalid = || PyTypeError::new_err("cells must be a 2D array of bool or integers.");
+        let array = cells.cast::<PyUntypedArray>().map_err(|_| invalid())?;
+        if array.ndim() != 2 || !matches!(array.dtype().kind(), b'b' | b'i' | b'u') {
+            return Err(invalid());
+        }
+        // any integer dtype is compared in numpy, rather than converted element by element
+        let alive = array.rich_compare(0, CompareOp::Ne)?;
+        let alive = alive.extract::<PyReadonlyArray2<'_, bool>>()?;
+        let [height, width] = [alive.shape()[0], alive.shape()[1]];
+        let alive = alive.as_array().iter().copied().collect::<Vec<_>>();
+        let cells = alive
+            .into_iter()
+            .map(|alive| if alive { CELL_ALIVE } else { CELL_DEAD })
+            .collect::<Vec<_>>();
+        let pattern = Pattern::new(width, height, &cells).map_err(value_error)?;
+        let rule = match rule {
+            Some(rule) => rule.parse().map_err(value_error)?,
+            None => Rule::default(),
+        };
+        Self::from_pattern(&pattern, rule, 0)
+    }
+
+    /// Create a world of a RLE pattern with a margin of dead cells, taking the rule of the pattern
+    #[staticmethod]
+    #[pyo3(signature = (text, margin = 0))]
+    fn from_rle(text: &str, margin: usize) -> PyResult<Self> {
+        let pattern = Pattern::from_rle(text).map_err(value_error)?;
+        let rule = match &pattern.rule {
+            Some(rule) => rule.parse().map_err(value_error)?,
+            None => Rule::default(),
+        };
+        Self::from_pattern(&pattern, rule, margin)
+    }
+
+    /// The cells as RLE with the rule
+    fn to_rle(&self) -> String {
+        let mut pattern = self.inner();
+        pattern.rule = Some(self.world.rule().to_definition());
+        pattern.to_rle()
+    }
+
+    /// The cells as a 2D uint8 array, 1 for alive, 0 for dead and 2 or more for dying cells of Generations rules
+    fn to_numpy<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<u8>> {
+        let pattern = self.inner();
+        Array2::from_shape_vec((pattern.height, pattern.width), pattern.cells)
+            .expect("invalid pattern size")
+            .into_pyarray(py)
+    }
+
+    /// Advance the world by the number of generations
+    #[pyo3(signature = (generations = 1))]
+    fn step(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.world.next();
+        }
+    }
+
+    /// `(height, width)` of the arrays
+    #[getter]
+    fn shape(&self) -> (usize, usize) {
+        (self.world.ny() - 2, self.world.nx() - 2)
+    }
+
+    #[getter]
+    fn generation(&self) -> usize {
+        self.world.generation()
+    }
+
+    #[getter]
+    fn population(&self) -> usize {
+        self.world.population()
+    }
+
+    /// Rule like `B3/S23`, or Generations rules like `B2/S/C3`
+    #[getter]
+    fn rule(&self) -> String {
+        self.world.rule().to_string()
+    }
+
+    #[setter]
+    fn set_rule(&mut self, rule: &str) -> PyResult<()> {
+        self.world.set_rule(rule.parse().map_err(value_error)?);
+        Ok(())
+    }
+
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        let (height, width) = self.shape();
+        format!(
+            "World(shape=({}, {}), rule='{}', generation={}, population={})",
+            height,
+            width,
+            self.world.rule(),
+            self.world.generation(),
+            self.world.population()
+        )
+    }
+}
+
+impl PyWorld {
+    fn from_pattern(pattern: &Pattern, rule: Rule, margin: usize) -> PyResult<Self> {
+        // the surrounding dead cells on both sides
+        let nx = pattern.width + 2 * margin + 2;
+        let ny = pattern.height + 2 * margin + 2;
+        let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny])
+            .map_err(value_error)?
+            .with_rule(rule);
+        world.paste(pattern, margin + 1, margin + 1);
+        Ok(PyWorld { world })
+    }
+
+    /// The cells without the surrounding dead cells
+    fn inner(&self) -> Pattern {
+        let (height, width) = self.shape();
+        self.world.extract(1, 1, width, height)
+    }
+}
+
+fn value_error(e: anyhow::Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+#[pymodule]
+fn lifegame(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWorld>()?;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+import numpy as np
+import pytest
+
+import lifegame
+
+GLIDER = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n"
+
+
+def test_numpy_round_trip():
+    cells = np.zeros((6, 8), dtype=np.uint8)
+    cells[2, 1:4] = 1
+    world = lifegame.World(cells)
+    assert world.shape == (6, 8)
+    assert (world.generation, world.population) == (0, 3)
+    np.testing.assert_array_equal(world.to_numpy(), cells)
+
+    world.step()
+    expected = np.zeros((6, 8), dtype=np.uint8)
+    expected[1:4, 2] = 1
+    np.testing.assert_array_equal(world.to_numpy(), expected)
+    assert world.generation == 1
+
+    # bool arrays and nonzero values are accepted.
+    np.testing.assert_array_equal(lifegame.World(cells == 1).to_numpy(), cells)
+    np.testing.assert_array_equal(lifegame.World(cells * 7).to_numpy(), cells)
+
+
+def test_integer_cells():
+    cells = np.zeros((4, 5), dtype=np.int64)
+    cells[1, 1:4] = [1, 256, -1]
+    expected = (cells != 0).astype(np.uint8)
+    np.testing.assert_array_equal(lifegame.World(cells).to_numpy(), expected)
+    np.testing.assert_array_equal(
+        lifegame.World(cells.astype(np.int32)).to_numpy(), expected
+    )
+
+
+def test_invalid_cells():
+    with pytest.raises(TypeError):
+        lifegame.World(np.zeros((2, 2), dtype=np.float64))
+    with pytest.raises(TypeError):
+        lifegame.World(np.zeros(4, dtype=np.uint8))
+    with pytest.raises(ValueError):
+        lifegame.World(np.zeros((2, 2), dtype=np.uint8), rule="B9/S")
+
+
+def test_rle():
+    world = lifegame.World.from_rle(GLIDER, margin=1)
+    assert world.shape == (5, 5)
+    assert world.population == 5
+    world.step(4)
+    assert world.to_rle() == "x = 5, y = 5, rule = B3/S23\n2$3bo$4bo$2b3o!\n"
+    with pytest.raises(ValueError):
+        lifegame.World.from_rle("x = 1, y = 1\n2o!")
+
+
+def test_rule_and_copy():
+    cells = np.zeros((5, 5), dtype=np.uint8)
+    world = lifegame.World(cells, rule="B36/S23")
+    assert world.rule == "B36/S23"
+    world.rule = "23/3"
+    assert world.rule == "B3/S23"
+    with pytest.raises(ValueError):
+        world.rule = "B9"
+
+    copied = world.copy()
+    copied.step(2)
+    assert (world.generation, copied.generation) == (0, 2)
+    assert repr(world) == "World(shape=(5, 5), rule='B3/S23', generation=0, population=0)"
@@ -0,0 +1,80 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lifegame_core::World;
+
+/// Extension of snapshot files
+pub const SNAPSHOT_EXTENSION: &str = "snapshot";
+
+/// Directory of snapshots named by their generations, keeping the latest ones.
+#[derive(Debug)]
+pub struct Checkpoints {
+    dir: PathBuf,
+    /// number of snapshots to keep, at least 1
+    keep: usize,
+}
+
+impl Checkpoints {
+    /// Open the directory, creating it if missing
+    pub fn open(dir: &Path, keep: usize) -> Result<Checkpoints> {
+        fs::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+        Ok(Checkpoints {
+            dir: dir.to_path_buf(),
+            keep: keep.max(1),
+        })
+    }
+
+    /// Write the snapshot of the world and remove the old ones.
+    ///
+    /// The snapshot is written to a temporary file and renamed,
+    /// so that a killed run never leaves a broken snapshot.
+    pub fn save(&self, world: &World) -> Result<PathBuf> {
+        let path = self.path(world.generation());
+        let temporary = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        world.write_snapshot(&mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        fs::rename(&temporary, &path)?;
+
+        let snapshots = self.list()?;
+        for (_, old) in &snapshots[..snapshots.len().saturating_sub(self.keep)] {
+            fs::remove_file(old)?;
+        }
+        Ok(path)
+    }
+
+    /// The world of the latest snapshot, or `None` if there is no snapshot
+    pub fn latest(&self) -> Result<Option<World>> {
+        let Some((_, path)) = self.list()?.pop() else {
+            return Ok(None);
+        };
+        let file = File::open(&path)?;
+        let world = World::read_snapshot(BufReader::new(file))
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        Ok(Some(world))
+    }
+
+    /// Snapshots in the directory as `(generation, path)` in ascending order of generations
+    pub fn list(&self) -> Result<Vec<(usize, PathBuf)>> {
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == SNAPSHOT_EXTENSION) {
+                let generation = path.file_stem().and_then(|s| s.to_str()?.parse().ok());
+                if let Some(generation) = generation {
+                    snapshots.push((generation, path));
+                }
+            }
+        }
+        snapshots.sort();
+        Ok(snapshots)
+    }
+
+    fn path(&self, generation: usize) -> PathBuf {
+        self.dir
+            .join(format!("{:020}.{}", generation, SNAPSHOT_EXTENSION))
+    }
+}
@@ -0,0 +1,126 @@
+pub mod checkpoint;
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use lifegame_core::{Cell, World, CELL_ALIVE, CELL_DEAD};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub use checkpoint::Checkpoints;
+
+/// Headless run of a world up to a generation with periodic snapshots.
+#[derive(Debug, Default)]
+pub struct Runner {
+    /// generation to stop at
+    pub last: usize,
+    /// write a snapshot at every multiple of the number of generations
+    pub every: Option<usize>,
+    /// write a snapshot when the time has passed since the last one
+    pub interval: Option<Duration>,
+}
+
+impl Runner {
+    /// Step the world up to the last generation, writing snapshots if `checkpoints` is given.
+    ///
+    /// The snapshot of the last generation is always written, and `on_save` is called with
+    /// the generation of every snapshot.
+    pub fn run(
+        &self,
+        world: &mut World,
+        checkpoints: Option<&Checkpoints>,
+        mut on_save: impl FnMut(usize),
+    ) -> Result<()> {
+        let mut saved = Instant::now();
+        while world.generation() < self.last {
+            world.next();
+            let Some(checkpoints) = checkpoints else {
+                continue;
+            };
+            let generation = world.generation();
+            let periodic = self
+                .every
+                .is_some_and(|n| n > 0 && generation.is_multiple_of(n));
+            let elapsed = self.interval.is_some_and(|t| saved.elapsed() >= t);
+            if periodic || elapsed || generation == self.last {
+                checkpoints.save(world)?;
+                saved = Instant::now();
+                on_save(generation);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Create a world of a random soup surrounded by the always-dead boundary
+pub fn soup(width: usize, height: usize, alive_prob: f64, seed: u64) -> Result<World> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let (nx, ny) = (width + 2, height + 2);
+    let cells = (0..nx * ny)
+        .map(|_| match rng.gen_bool(alive_prob) {
+            true => CELL_ALIVE,
+            false => CELL_DEAD,
+        })
+        .collect::<Vec<Cell>>();
+    World::new(nx, ny, &cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_resume() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("lifegame-runner-{}", std::process::id()));
+        let initial = soup(48, 32, 0.35, 7)?;
+
+        let mut uninterrupted = initial.clone();
+        Runner {
+            last: 300,
+            ..Runner::default()
+        }
+        .run(&mut uninterrupted, None, |_| {})?;
+        assert_eq!(uninterrupted.generation(), 300);
+
+        // a run killed at generation 137, after the snapshot of generation 130
+        let checkpoints = Checkpoints::open(&dir, 2)?;
+        let mut saved = Vec::new();
+        let mut killed = initial.clone();
+        let runner = Runner {
+            last: 137,
+            every: Some(10),
+            interval: None,
+        };
+        runner.run(&mut killed, Some(&checkpoints), |g| saved.push(g))?;
+        fs::remove_file(checkpoints.list()?.pop().unwrap().1)?;
+        assert_eq!(saved.len(), 14);
+        assert_eq!(
+            checkpoints.list()?.iter().map(|s| s.0).collect::<Vec<_>>(),
+            [130]
+        );
+
+        let mut resumed = checkpoints.latest()?.unwrap();
+        assert_eq!(resumed.generation(), 130);
+        let runner = Runner {
+            last: 300,
+            every: Some(100),
+            interval: None,
+        };
+        runner.run(&mut resumed, Some(&checkpoints), |_| {})?;
+        assert_eq!(resumed.generation(), uninterrupted.generation());
+        assert_eq!(resumed.rule(), uninterrupted.rule());
+        assert_eq!(resumed.present_cells(), uninterrupted.present_cells());
+        assert_eq!(
+            checkpoints.list()?.iter().map(|s| s.0).collect::<Vec<_>>(),
+            [200, 300]
+        );
+        assert_eq!(
+            checkpoints.latest()?.unwrap().present_cells(),
+            uninterrupted.present_cells()
+        );
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Error, Result};
+use lifegame_core::{Pattern, Rule, World, CELL_DEAD};
+use lifegame_runner::{soup, Checkpoints, Runner};
+
+const USAGE: &str = "\
+usage: lifegame-runner [INPUT] --last N [OPTIONS]
+
+Steps a world without display up to generation N and prints it in RLE.
+INPUT is a pattern file (RLE, plaintext or macrocell); a random soup is made without INPUT.
+With --checkpoint, snapshots are written to DIR periodically, and the run resumes
+from the latest snapshot in DIR if there is one, ignoring INPUT.
+
+options:
+  --last N          generation to stop at
+  --checkpoint DIR  directory of snapshots
+  --every N         write a snapshot every N generations
+  --interval SECS   write a snapshot every SECS seconds (default 300)
+  --keep N          number of snapshots to keep (default 2)
+  --rule RULE       rule like B3/S23 (default the rule of INPUT or B3/S23)
+  --margin N        dead cells around the pattern (default 10)
+  --size WxH        size of the soup (default 256x256)
+  --density P       alive cell probability of the soup (default 0.3)
+  --seed N          seed of the soup (default 0)
+  --output FILE     write the final world to FILE instead of printing";
+
+/// Command line options.
+struct Args {
+    input: Option<PathBuf>,
+    last: Option<usize>,
+    checkpoint: Option<PathBuf>,
+    every: Option<usize>,
+    interval: u64,
+    keep: usize,
+    rule: Option<Rule>,
+    margin: usize,
+    size: (usize, usize),
+    density: f64,
+    seed: u64,
+    output: Option<PathBuf>,
+}
+
+impl Args {
+    fn parse() -> Result<Self> {
+        let mut iter = std::env::args().skip(1);
+        let mut args = Self {
+            input: None,
+            last: None,
+            checkpoint: None,
+            every: None,
+            interval: 300,
+            keep: 2,
+            rule: None,
+            margin: 10,
+            size: (256, 256),
+            density: 0.3,
+            seed: 0,
+            output: None,
+        };
+        while let Some(arg) = iter.next() {
+            if arg == "-h" || arg == "--help" {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            if !arg.starts_with("--") {
+                if args.input.replace(PathBuf::from(arg)).is_some() {
+                    return Err(Error::msg(USAGE));
+                }
+                continue;
+            }
+            let value = iter
+                .next()
+                .with_context(|| format!("missing value of {}", arg))?;
+            match arg.as_str() {
+                "--last" => args.last = Some(value.parse()?),
+                "--checkpoint" => args.checkpoint = Some(PathBuf::from(value)),
+                "--every" => args.every = Some(value.parse()?),
+                "--interval" => args.interval = value.parse()?,
+                "--keep" => args.keep = value.parse()?,
+                "--rule" => args.rule = Some(value.parse()?),
+                "--margin" => args.margin = value.parse()?,
+                "--size" => args.size = parse_size(&value)?,
+                "--density" => args.density = value.parse()?,
+                "--seed" => args.seed = value.parse()?,
+                "--output" => args.output = Some(PathBuf::from(value)),
+                _ => return Err(Error::msg(format!("unknown option {}\n\n{}", arg, USAGE))),
+            }
+        }
+        if args.last.is_none() || !(0.0..=1.0).contains(&args.density) {
+            return Err(Error::msg(USAGE));
+        }
+        Ok(args)
+    }
+
+    /// The initial world of the input pattern or the soup
+    fn initial_world(&self) -> Result<World> {
+        let Some(input) = &self.input else {
+            let (width, height) = self.size;
+            let world = soup(width, height, self.density, self.seed)?;
+            return Ok(world.with_rule(self.rule.clone().unwrap_or_default()));
+        };
+        let text = fs::read_to_string(input)
+            .with_context(|| format!("cannot read {}", input.display()))?;
+        let pattern = Pattern::parse(&text)?;
+        let rule = match (&self.rule, &pattern.rule) {
+            (Some(rule), _) => rule.clone(),
+            (None, Some(rule)) => rule.parse()?,
+            (None, None) => Rule::default(),
+        };
+
+        // the pattern surrounded by the margin and the always-dead boundary
+        let offset = self.margin + 1;
+        let nx = pattern.width + 2 * offset;
+        let ny = pattern.height + 2 * offset;
+        let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny])?.with_rule(rule);
+        world.paste(&pattern, offset, offset);
+        Ok(world)
+    }
+}
+
+/// Parse a size written as `WxH`
+fn parse_size(text: &str) -> Result<(usize, usize)> {
+    let (width, height) = text
+        .split_once('x')
+        .with_context(|| format!("invalid size {}", text))?;
+    Ok((width.trim().parse()?, height.trim().parse()?))
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse()?;
+    let checkpoints = match &args.checkpoint {
+        Some(dir) => Some(Checkpoints::open(dir, args.keep)?),
+        None => None,
+    };
+    let latest = match &checkpoints {
+        Some(checkpoints) => checkpoints.latest()?,
+        None => None,
+    };
+    let mut world = match latest {
+        Some(world) => {
+            eprintln!("resuming from generation {}", world.generation());
+            world
+        }
+        None => args.initial_world()?,
+    };
+
+    let runner = Runner {
+        last: args.last.unwrap_or_default(),
+        every: args.every,
+        interval: Some(Duration::from_secs(args.interval)),
+    };
+    runner.run(&mut world, checkpoints.as_ref(), |generation| {
+        eprintln!("saved generation {}", generation);
+    })?;
+
+    let (width, height) = (world.nx() - 2, world.ny() - 2);
+    let mut pattern = world.extract(1, 1, width, height);
+    pattern.rule = Some(world.rule().to_definition());
+    match &args.output {
+        Some(output) => fs::write(output, pattern.to_rle())
+            .with_context(|| format!("cannot write {}", output.display()))?,
+        None => print!("{}", pattern.to_rle()),
+    }
+    Ok(())
+}
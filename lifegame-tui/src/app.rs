@@ -1,7 +1,28 @@
 use std::error;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
 
-use lifegame_core::{Cell, World, CELL_ALIVE, CELL_DEAD};
-use rand::Rng;
+use lifegame_core::{Cell, Pattern, World, CELL_ALIVE, CELL_DEAD};
+use lifegame_export::{Animation, Format, ImageFormat, Snapshot, Style};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use ratatui::layout::{Margin, Rect};
+
+use crate::command::Command;
+use crate::config::{Config, ExportConfig};
+use crate::dialog::{FileBrowser, LibraryBrowser, Popup, SavePrompt};
+use crate::keymap::KeyMap;
+use crate::minimap::Minimap;
+use crate::record::Recorder;
+use crate::theme::{self, Theme};
+
+/// Maximum minimap size including the border
+const MINIMAP_MAX_WIDTH: usize = 34;
+const MINIMAP_MAX_HEIGHT: usize = 14;
+
+/// Margin around the selection to track the followed object in
+const FOLLOW_MARGIN: usize = 3;
 
 /// Application result type.
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
@@ -32,10 +53,79 @@ pub struct App {
     pub rendering_ix: usize,
     /// rendering cell index along with y-axis
     pub rendering_iy: usize,
+    /// key bindings
+    pub keymap: KeyMap,
+    /// color theme
+    pub theme: Theme,
+    /// color cells by their age
+    pub age_coloring: bool,
+    /// last clicked cell
+    pub cursor: Option<(usize, usize)>,
+    /// selected cells
+    pub selection: Option<Selection>,
+    /// terminal area where the world is rendered
+    pub world_area: Rect,
+    /// popup dialog
+    pub popup: Option<Popup>,
+    /// message shown in the status line
+    pub status: Option<String>,
+    /// resize the world to the terminal whenever the terminal is resized
+    pub fit_to_terminal: bool,
+    /// show the minimap when the world is larger than the terminal
+    pub show_minimap: bool,
+    /// camera mode
+    pub camera: Camera,
+    /// seed of random soups
+    pub seed: u64,
+    /// number of resets, so that every reset makes a different soup from the seed
+    pub resets: u64,
+    /// recorder of applied commands
+    pub recorder: Option<Recorder>,
+    /// replaying a recording, where the world area follows the recording instead of the terminal
+    pub replaying: bool,
+    /// image export settings
+    pub export: ExportConfig,
+}
+
+/// Camera mode deciding how the rendering area moves.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Camera {
+    /// Panned only by the user
+    Free,
+    /// Follow the bounding box of all alive cells
+    FollowAll,
+    /// Follow the centroid of the object in the tracking window `(ix, iy, width, height)`.
+    /// The window moves along with the object.
+    FollowObject((usize, usize, usize, usize)),
+}
+
+/// Rectangular selection of cells between two corners.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Selection {
+    /// the corner where the selection started
+    pub start: (usize, usize),
+    /// the opposite corner
+    pub end: (usize, usize),
+}
+
+impl Selection {
+    /// Top-left corner and size of the selection as `(ix, iy, width, height)`
+    pub fn rect(&self) -> (usize, usize, usize, usize) {
+        let (ix, iy) = (self.start.0.min(self.end.0), self.start.1.min(self.end.1));
+        let width = self.start.0.abs_diff(self.end.0) + 1;
+        let height = self.start.1.abs_diff(self.end.1) + 1;
+        (ix, iy, width, height)
+    }
+
+    /// Check if the cell is selected
+    pub fn contains(&self, ix: usize, iy: usize) -> bool {
+        let (x, y, width, height) = self.rect();
+        (x..x + width).contains(&ix) && (y..y + height).contains(&iy)
+    }
 }
 
-fn random_cells(nx: usize, ny: usize, alive_prob: f64) -> Vec<Cell> {
-    let mut rng = rand::thread_rng();
+fn random_cells(nx: usize, ny: usize, alive_prob: f64, seed: u64) -> Vec<Cell> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
     let size = nx * ny;
     (0..size)
         .map(|_| match rng.gen_bool(alive_prob) {
@@ -47,10 +137,25 @@ fn random_cells(nx: usize, ny: usize, alive_prob: f64) -> Vec<Cell> {
 
 impl Default for App {
     fn default() -> Self {
-        let (nx, ny) = (120, 60);
-        let alive_prob = 0.2;
-        let cells = random_cells(nx, ny, alive_prob);
-        let world = World::new(nx, ny, &cells).expect("invalid size!");
+        Self::with_config(&Config::default())
+    }
+}
+
+impl App {
+    /// Constructs a new instance of [`App`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new instance of [`App`] from the configuration.
+    pub fn with_config(config: &Config) -> Self {
+        let (nx, ny) = (config.world.nx, config.world.ny);
+        let alive_prob = config.world.alive_prob;
+        let seed = config.world.seed.unwrap_or_else(rand::random);
+        let cells = random_cells(nx, ny, alive_prob, seed);
+        let world = World::new(nx, ny, &cells)
+            .expect("invalid size!")
+            .with_rule(config.world.rule.clone());
         Self {
             alive_prob,
             gen: 0,
@@ -60,14 +165,78 @@ impl Default for App {
             world,
             rendering_ix: 0,
             rendering_iy: 0,
+            keymap: KeyMap::with_overrides(&config.keymap),
+            theme: config.theme(),
+            age_coloring: config.ui.age_coloring,
+            cursor: None,
+            selection: None,
+            world_area: Rect::default(),
+            popup: None,
+            status: None,
+            fit_to_terminal: config.world.fit_to_terminal,
+            show_minimap: config.ui.minimap,
+            camera: Camera::Free,
+            seed,
+            resets: 0,
+            recorder: None,
+            replaying: false,
+            export: config.export.clone(),
         }
     }
-}
 
-impl App {
-    /// Constructs a new instance of [`App`].
-    pub fn new() -> Self {
-        Self::default()
+    /// Apply the command, logging it if recording.
+    ///
+    /// Ticks while paused change nothing and are not logged.
+    pub fn apply(&mut self, command: Command) -> AppResult<()> {
+        if command == Command::Tick && self.state != AppState::Run {
+            return Ok(());
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.log(&command)?;
+        }
+        match command {
+            Command::Tick => self.tick(),
+            Command::Toggle => self.toggle(),
+            Command::Reset => self.reset()?,
+            Command::Pan { dx, dy } => {
+                self.camera = Camera::Free;
+                self.pan_x(dx);
+                self.pan_y(dy);
+            }
+            Command::Jump { ix, iy } => {
+                self.camera = Camera::Free;
+                self.center_on(ix, iy);
+            }
+            Command::CenterWorld => self.center_world(),
+            Command::FitPattern => self.fit_pattern(),
+            Command::FitWorld => {
+                self.fit_world_to_terminal()?;
+                self.clamp_viewport();
+            }
+            Command::Resize {
+                x,
+                y,
+                width,
+                height,
+            } => self.resize(Rect::new(x, y, width, height))?,
+            Command::Click { ix, iy } => {
+                self.cursor = Some((ix, iy));
+                self.selection = None;
+            }
+            Command::Select { start, end } => self.selection = Some(Selection { start, end }),
+            Command::LoadPattern { rle } => self.load_pattern(&Pattern::from_rle(&rle)?)?,
+            Command::InsertPattern { rle } => {
+                let pattern = Pattern::from_rle(&rle)?;
+                let (ix, iy) = self.insert_pattern(&pattern);
+                let name = pattern.name.as_deref().unwrap_or("pattern");
+                self.set_status(format!("inserted {} at ({}, {})", name, ix, iy));
+            }
+            Command::ToggleFollow => self.toggle_follow(),
+            Command::ToggleMinimap => self.toggle_minimap(),
+            Command::CycleTheme => self.cycle_theme(),
+            Command::ToggleAgeColoring => self.toggle_age_coloring(),
+        }
+        Ok(())
     }
 
     pub fn can_reset(&self) -> bool {
@@ -79,6 +248,7 @@ impl App {
         if self.state == AppState::Run {
             self.gen = self.gen.saturating_add(1);
             self.world.next();
+            self.update_camera();
         }
     }
 
@@ -94,31 +264,372 @@ impl App {
     /// Reset lifegame
     pub fn reset(&mut self) -> AppResult<()> {
         if self.can_reset() {
-            let cells = random_cells(self.nx, self.ny, self.alive_prob);
-            self.world = World::new(self.nx, self.ny, &cells)?;
+            self.resets += 1;
+            let seed = self.seed.wrapping_add(self.resets);
+            let cells = random_cells(self.nx, self.ny, self.alive_prob, seed);
+            self.world = World::new(self.nx, self.ny, &cells)?.with_rule(self.world.rule().clone());
             self.gen = 0;
             self.rendering_ix = 0;
             self.rendering_iy = 0;
+            self.cursor = None;
+            self.selection = None;
+            self.camera = Camera::Free;
         }
         Ok(())
     }
 
     /// Pan rendering offset along with x-axis
     pub fn pan_x(&mut self, shift: isize) {
-        self.rendering_ix = Self::calculate_panned_index(self.rendering_ix, shift, self.nx);
+        let upper_limit = self.nx.saturating_sub(self.world_area.width as usize);
+        self.rendering_ix = Self::calculate_panned_index(self.rendering_ix, shift, upper_limit);
     }
 
     /// Pan rendering offset along with y-axis
     pub fn pan_y(&mut self, shift: isize) {
-        self.rendering_iy = Self::calculate_panned_index(self.rendering_iy, shift, self.ny);
+        let upper_limit = self.ny.saturating_sub(self.world_area.height as usize);
+        self.rendering_iy = Self::calculate_panned_index(self.rendering_iy, shift, upper_limit);
     }
 
     fn calculate_panned_index(current: usize, shift: isize, upper_limit: usize) -> usize {
-        if shift < 0 {
-            current.saturating_add_signed(shift)
-        } else {
-            current.saturating_add_signed(shift).min(upper_limit)
+        current.saturating_add_signed(shift).min(upper_limit)
+    }
+
+    /// Keep the rendering area inside the world
+    pub fn clamp_viewport(&mut self) {
+        self.pan_x(0);
+        self.pan_y(0);
+    }
+
+    /// Pan the rendering area so that the cell is at its center
+    pub fn center_on(&mut self, ix: usize, iy: usize) {
+        self.rendering_ix = ix.saturating_sub(self.world_area.width as usize / 2);
+        self.rendering_iy = iy.saturating_sub(self.world_area.height as usize / 2);
+        self.clamp_viewport();
+    }
+
+    /// Pan the rendering area to the center of the world
+    pub fn center_world(&mut self) {
+        self.center_on(self.nx / 2, self.ny / 2);
+    }
+
+    /// Pan the rendering area to the center of the alive cells
+    pub fn fit_pattern(&mut self) {
+        match self.world.bounding_box() {
+            Some((ix, iy, width, height)) => {
+                self.center_on(ix + width / 2, iy + height / 2);
+                if width > self.world_area.width as usize
+                    || height > self.world_area.height as usize
+                {
+                    self.set_status(format!(
+                        "pattern {}x{} is larger than the view",
+                        width, height
+                    ));
+                }
+            }
+            None => self.set_status("no alive cells"),
+        }
+    }
+
+    /// Start following the selected object, or all alive cells if nothing is selected.
+    /// Stop following if already following.
+    pub fn toggle_follow(&mut self) {
+        self.camera = match (self.camera, self.selection) {
+            (Camera::Free, Some(selection)) => {
+                let (ix, iy, width, height) = selection.rect();
+                Camera::FollowObject((
+                    ix.saturating_sub(FOLLOW_MARGIN),
+                    iy.saturating_sub(FOLLOW_MARGIN),
+                    width + 2 * FOLLOW_MARGIN,
+                    height + 2 * FOLLOW_MARGIN,
+                ))
+            }
+            (Camera::Free, None) => Camera::FollowAll,
+            _ => Camera::Free,
+        };
+        self.update_camera();
+    }
+
+    /// Pan the rendering area to the followed cells
+    pub fn update_camera(&mut self) {
+        match self.camera {
+            Camera::Free => {}
+            Camera::FollowAll => match self.world.bounding_box() {
+                Some((ix, iy, width, height)) => self.center_on(ix + width / 2, iy + height / 2),
+                None => self.lose_camera_target(),
+            },
+            Camera::FollowObject((ix, iy, width, height)) => {
+                match self.world.centroid_in(ix, iy, width, height) {
+                    Some((cx, cy)) => {
+                        let (cx, cy) = (cx.round() as usize, cy.round() as usize);
+                        let window = (
+                            cx.saturating_sub(width / 2),
+                            cy.saturating_sub(height / 2),
+                            width,
+                            height,
+                        );
+                        self.camera = Camera::FollowObject(window);
+                        self.center_on(cx, cy);
+                    }
+                    None => self.lose_camera_target(),
+                }
+            }
+        }
+    }
+
+    fn lose_camera_target(&mut self) {
+        self.camera = Camera::Free;
+        self.set_status("no alive cells to follow");
+    }
+
+    /// Called when the terminal is resized and the world is rendered in the area
+    pub fn resize(&mut self, world_area: Rect) -> AppResult<()> {
+        self.world_area = world_area;
+        if self.fit_to_terminal {
+            self.fit_world_to_terminal()?;
         }
+        self.clamp_viewport();
+        Ok(())
+    }
+
+    /// Resize the world to the rendering area, keeping the cells
+    pub fn fit_world_to_terminal(&mut self) -> AppResult<()> {
+        let nx = (self.world_area.width as usize).max(3);
+        let ny = (self.world_area.height as usize).max(3);
+        if (nx, ny) == (self.nx, self.ny) {
+            return Ok(());
+        }
+        self.world = self.world.resized(nx, ny)?;
+        self.nx = nx;
+        self.ny = ny;
+        self.rendering_ix = 0;
+        self.rendering_iy = 0;
+        if self.cursor.is_some_and(|(ix, iy)| ix >= nx || iy >= ny) {
+            self.cursor = None;
+        }
+        if self
+            .selection
+            .is_some_and(|s| s.start.0.max(s.end.0) >= nx || s.start.1.max(s.end.1) >= ny)
+        {
+            self.selection = None;
+        }
+        Ok(())
+    }
+
+    /// Switch to the next built-in color theme
+    pub fn cycle_theme(&mut self) {
+        self.theme = self.theme.next();
+    }
+
+    /// Enable/disable coloring cells by their age
+    pub fn toggle_age_coloring(&mut self) {
+        self.age_coloring = !self.age_coloring;
+    }
+
+    /// Cell index at the terminal position, if a cell is rendered there
+    pub fn cell_at(&self, column: u16, row: u16) -> Option<(usize, usize)> {
+        let area = self.world_area;
+        if !(area.left()..area.right()).contains(&column)
+            || !(area.top()..area.bottom()).contains(&row)
+        {
+            return None;
+        }
+        let ix = self.rendering_ix + (column - area.x) as usize;
+        let iy = self.rendering_iy + (row - area.y) as usize;
+        (ix < self.nx && iy < self.ny).then_some((ix, iy))
+    }
+
+    /// Show a message in the status line
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.status = Some(message.into());
+    }
+
+    /// Show the file browser to open a pattern
+    pub fn show_open_dialog(&mut self) {
+        match FileBrowser::new(Path::new(".")) {
+            Ok(browser) => self.popup = Some(Popup::Open(browser)),
+            Err(e) => self.set_status(format!("cannot list files: {}", e)),
+        }
+    }
+
+    /// Show the prompt to save the world or the selection
+    pub fn show_save_dialog(&mut self) {
+        self.popup = Some(Popup::Save(SavePrompt::new("pattern.rle")));
+    }
+
+    /// Show the prompt to export an animation of the world or the selection
+    pub fn show_export_dialog(&mut self) {
+        self.popup = Some(Popup::Export(SavePrompt::new("lifegame.gif")));
+    }
+
+    /// Show the prompt to export an image of the world or the selection
+    pub fn show_snapshot_dialog(&mut self) {
+        self.popup = Some(Popup::Snapshot(SavePrompt::new("lifegame.png")));
+    }
+
+    /// Show the built-in pattern library
+    pub fn show_library(&mut self) {
+        self.popup = Some(Popup::Library(LibraryBrowser::default()));
+    }
+
+    /// Paste the pattern with its top-left corner at the cursor,
+    /// or at the center of the rendering area if there is no cursor.
+    /// Returns where the pattern is pasted.
+    pub fn insert_pattern(&mut self, pattern: &Pattern) -> (usize, usize) {
+        let (ix, iy) = self.cursor.unwrap_or_else(|| {
+            let area = self.world_area;
+            (
+                (self.rendering_ix + area.width as usize / 2).saturating_sub(pattern.width / 2),
+                (self.rendering_iy + area.height as usize / 2).saturating_sub(pattern.height / 2),
+            )
+        });
+        self.world.paste(pattern, ix, iy);
+        (ix, iy)
+    }
+
+    /// Open a pattern file (RLE or plaintext) into the world
+    pub fn open_pattern(&mut self, path: &Path) -> AppResult<()> {
+        let text = fs::read_to_string(path)?;
+        let pattern = Pattern::parse(&text)?;
+        self.apply(Command::LoadPattern {
+            rle: pattern.to_rle(),
+        })
+    }
+
+    /// Replace the world with the pattern at its center, taking the rule of the pattern if any.
+    /// The world grows if the pattern does not fit.
+    pub fn load_pattern(&mut self, pattern: &Pattern) -> AppResult<()> {
+        let rule = match &pattern.rule {
+            Some(rule) => rule.parse()?,
+            None => self.world.rule().clone(),
+        };
+        let nx = self.nx.max(pattern.width + 2);
+        let ny = self.ny.max(pattern.height + 2);
+        let mut world = World::new(nx, ny, &vec![CELL_DEAD; nx * ny])?.with_rule(rule);
+        let (ix, iy) = ((nx - pattern.width) / 2, (ny - pattern.height) / 2);
+        world.paste(pattern, ix, iy);
+        self.world = world;
+        self.nx = nx;
+        self.ny = ny;
+        self.gen = 0;
+        if self.state == AppState::Run {
+            self.state = AppState::Pause;
+        }
+        self.cursor = None;
+        self.selection = None;
+        self.center_on(ix + pattern.width / 2, iy + pattern.height / 2);
+        Ok(())
+    }
+
+    /// Save the selection, or the whole world if nothing is selected.
+    /// The format is plaintext for `.cells` and `.txt` files, and RLE with the rule otherwise.
+    pub fn save_pattern(&self, path: &Path) -> AppResult<()> {
+        let mut pattern = match self.selection {
+            Some(selection) => {
+                let (ix, iy, width, height) = selection.rect();
+                self.world.extract(ix, iy, width, height)
+            }
+            None => self.world.extract(1, 1, self.nx - 2, self.ny - 2),
+        };
+        pattern.rule = Some(self.world.rule().to_definition());
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        let text = match extension.as_deref() {
+            Some("cells") | Some("txt") => pattern.to_plaintext(),
+            _ => pattern.to_rle(),
+        };
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Export an animation of the selection, or the whole world if nothing is selected,
+    /// from the present generation. The format is APNG for `.png` and `.apng` files, and GIF otherwise.
+    pub fn export_animation(&self, path: &Path) -> AppResult<()> {
+        let animation = Animation {
+            first: 0,
+            last: self.export.generations,
+            delay: self.export.delay,
+            region: self.selection.map(|selection| selection.rect()),
+            style: self.export_style(),
+        };
+        let format = Format::from_path(path).unwrap_or(Format::Gif);
+        let file = BufWriter::new(File::create(path)?);
+        animation.write(&self.world, format, file)?;
+        Ok(())
+    }
+
+    /// Export an image of the selection, or the whole world if nothing is selected.
+    /// The format is SVG for `.svg` files, and PNG otherwise.
+    pub fn export_snapshot(&self, path: &Path) -> AppResult<()> {
+        let snapshot = Snapshot {
+            region: self.selection.map(|selection| selection.rect()),
+            style: self.export_style(),
+        };
+        let format = ImageFormat::from_path(path).unwrap_or(ImageFormat::Png);
+        let file = BufWriter::new(File::create(path)?);
+        snapshot.write(&self.world, format, file)?;
+        Ok(())
+    }
+
+    /// Style of exported images by the settings and the theme
+    fn export_style(&self) -> Style {
+        let style = Style {
+            cell_size: self.export.cell_size,
+            grid: self.export.grid_color.and_then(theme::rgb),
+            ..Style::default()
+        };
+        self.theme.export_style(style)
+    }
+
+    /// Show/hide the minimap
+    pub fn toggle_minimap(&mut self) {
+        self.show_minimap = !self.show_minimap;
+    }
+
+    /// Minimap fitting the world into the corner of the rendering area,
+    /// or `None` if the whole world is already rendered
+    pub fn minimap(&self) -> Option<Minimap> {
+        let area = self.world_area;
+        if !self.show_minimap || (self.nx <= area.width as usize && self.ny <= area.height as usize)
+        {
+            return None;
+        }
+        // leave room for the border
+        let max_width = (area.width as usize / 4).min(MINIMAP_MAX_WIDTH);
+        let max_height = (area.height as usize / 3).min(MINIMAP_MAX_HEIGHT);
+        (max_width > 2 && max_height > 2)
+            .then(|| Minimap::new(self.nx, self.ny, max_width - 2, max_height - 2))
+    }
+
+    /// Terminal area where the minimap is rendered in the top-right corner of the world area,
+    /// empty if hidden
+    pub fn minimap_area(&self) -> Rect {
+        match self.minimap() {
+            Some(minimap) => {
+                let width = (minimap.width + 2) as u16;
+                let height = (minimap.height + 2) as u16;
+                let area = self.world_area;
+                Rect::new(area.right() - width, area.y, width, height)
+            }
+            None => Rect::default(),
+        }
+    }
+
+    /// Pan the rendering area to the world cell under the terminal position in the minimap.
+    /// Returns `false` if the position is out of the minimap.
+    pub fn jump_by_minimap(&mut self, column: u16, row: u16) -> AppResult<bool> {
+        let inner = self.minimap_area().inner(Margin::new(1, 1));
+        let Some(minimap) = self.minimap() else {
+            return Ok(false);
+        };
+        if !(inner.left()..inner.right()).contains(&column)
+            || !(inner.top()..inner.bottom()).contains(&row)
+        {
+            return Ok(false);
+        }
+        let (ix, iy) = minimap.to_world((column - inner.x) as usize, (row - inner.y) as usize);
+        self.apply(Command::Jump { ix, iy })?;
+        Ok(true)
     }
 
     /// Set running to false to quit the application.
@@ -126,3 +637,213 @@ impl App {
         self.state = AppState::Quit;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_rect() {
+        let selection = Selection {
+            start: (5, 2),
+            end: (3, 4),
+        };
+        assert_eq!(selection.rect(), (3, 2, 3, 3));
+        assert!(selection.contains(3, 4));
+        assert!(!selection.contains(6, 2));
+    }
+
+    #[test]
+    fn test_load_and_save_pattern() -> AppResult<()> {
+        let mut app = App::new();
+        let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!")?;
+        app.load_pattern(&glider)?;
+        assert_eq!(
+            app.world
+                .extract(1, 1, app.nx - 2, app.ny - 2)
+                .cells
+                .iter()
+                .filter(|&&c| c == CELL_ALIVE)
+                .count(),
+            5
+        );
+
+        let (ix, iy) = ((app.nx - 3) / 2, (app.ny - 3) / 2);
+        app.selection = Some(Selection {
+            start: (ix, iy),
+            end: (ix + 2, iy + 2),
+        });
+        let path =
+            std::env::temp_dir().join(format!("lifegame-tui-test-{}.cells", std::process::id()));
+        app.save_pattern(&path)?;
+        let saved = fs::read_to_string(&path)?;
+        fs::remove_file(&path)?;
+        assert_eq!(saved, ".O.\n..O\nOOO\n");
+
+        // the world grows to fit a large pattern
+        let large = Pattern::new(200, 3, &[CELL_ALIVE; 600])?;
+        app.load_pattern(&large)?;
+        assert_eq!((app.nx, app.ny), (202, 60));
+        Ok(())
+    }
+
+    #[test]
+    fn test_generations_rule() -> AppResult<()> {
+        let mut app = App::new();
+        let pattern = Pattern::from_rle("x = 2, y = 1, rule = /2/3\n2A!")?;
+        app.load_pattern(&pattern)?;
+        assert_eq!(app.world.rule().to_string(), "B2/S/C3");
+        app.state = AppState::Run;
+        app.tick();
+        assert!(app.world.present_cells().contains(&2));
+
+        let path =
+            std::env::temp_dir().join(format!("lifegame-tui-test-{}.rle", std::process::id()));
+        app.save_pattern(&path)?;
+        let saved = Pattern::from_rle(&fs::read_to_string(&path)?)?;
+        fs::remove_file(&path)?;
+        assert_eq!(saved.rule.as_deref(), Some("B2/S/C3"));
+        assert!(saved.cells.contains(&2));
+
+        app.state = AppState::Pause;
+        app.reset()?;
+        assert_eq!(app.world.rule().states(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rule_table() -> AppResult<()> {
+        let table = "@RULE Turn\n@TABLE\nn_states:3\nneighborhood:vonNeumann\n\
+                     symmetries:none\n0,1,0,0,0,2\n2,0,0,0,0,1\n";
+        let mut app = App::new();
+        app.load_pattern(&Pattern {
+            rule: Some(table.to_string()),
+            ..Pattern::from_rle("x = 2, y = 1\nAB!")?
+        })?;
+        assert_eq!(app.world.rule().to_string(), "Turn");
+
+        // saved with the rule file, which the name alone is not enough to load
+        let path = std::env::temp_dir().join(format!(
+            "lifegame-tui-test-table-{}.rle",
+            std::process::id()
+        ));
+        app.save_pattern(&path)?;
+        let saved = Pattern::from_rle(&fs::read_to_string(&path)?)?;
+        fs::remove_file(&path)?;
+        let mut loaded = App::new();
+        loaded.load_pattern(&saved)?;
+        assert_eq!(loaded.world.rule(), app.world.rule());
+        assert_eq!(loaded.world.present_cells(), app.world.present_cells());
+        Ok(())
+    }
+
+    #[test]
+    fn test_export() -> AppResult<()> {
+        let mut app = App::new();
+        app.selection = Some(Selection {
+            start: (1, 1),
+            end: (10, 5),
+        });
+        let path =
+            std::env::temp_dir().join(format!("lifegame-tui-test-{}.gif", std::process::id()));
+        app.export_animation(&path)?;
+        let data = fs::read(&path)?;
+        fs::remove_file(&path)?;
+        assert!(data.starts_with(b"GIF89a"));
+        // the selection of 10x5 cells, 4 pixels per cell
+        assert_eq!(data[6..10], [40, 0, 20, 0]);
+
+        let path =
+            std::env::temp_dir().join(format!("lifegame-tui-test-{}.svg", std::process::id()));
+        app.export_snapshot(&path)?;
+        let svg = fs::read_to_string(&path)?;
+        fs::remove_file(&path)?;
+        assert!(svg.contains(r#"width="40" height="20""#));
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_pattern() {
+        let mut app = App::new();
+        app.cursor = Some((10, 20));
+        let entry = lifegame_core::library::search("glider").next().unwrap();
+        assert_eq!(app.insert_pattern(&entry.pattern()), (10, 20));
+        assert_eq!(app.world.extract(10, 20, 3, 3), {
+            let mut pattern = entry.pattern();
+            pattern.name = None;
+            pattern
+        });
+    }
+
+    #[test]
+    fn test_viewport() -> AppResult<()> {
+        let mut app = App::new();
+        app.resize(Rect::new(0, 1, 100, 40))?;
+        assert_eq!(app.minimap_area(), Rect::new(78, 1, 22, 12));
+        app.pan_x(1000);
+        app.pan_y(1000);
+        assert_eq!((app.rendering_ix, app.rendering_iy), (20, 20));
+        app.pan_x(-5);
+        assert_eq!(app.rendering_ix, 15);
+
+        // a larger terminal shows the whole world
+        app.resize(Rect::new(0, 1, 200, 100))?;
+        assert_eq!((app.rendering_ix, app.rendering_iy), (0, 0));
+        assert_eq!(app.minimap_area(), Rect::default());
+
+        app.resize(Rect::new(0, 1, 20, 10))?;
+        app.center_world();
+        assert_eq!((app.rendering_ix, app.rendering_iy), (50, 25));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fit_world_to_terminal() -> AppResult<()> {
+        let mut app = App::new();
+        let cells = app.world.extract(0, 0, 50, 20);
+        app.fit_to_terminal = true;
+        app.resize(Rect::new(0, 1, 50, 20))?;
+        assert_eq!((app.nx, app.ny), (50, 20));
+        let kept = app.world.extract(1, 1, 48, 18);
+        assert_eq!(kept.cells[..48], cells.cells[51..99]);
+
+        app.resize(Rect::new(0, 1, 80, 30))?;
+        assert_eq!((app.nx, app.ny), (80, 30));
+        assert_eq!(app.world.extract(1, 1, 48, 18), kept);
+        Ok(())
+    }
+
+    #[test]
+    fn test_follow_object() -> AppResult<()> {
+        let mut app = App::new();
+        let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!")?;
+        app.load_pattern(&Pattern::new(40, 40, &[CELL_DEAD; 1600])?)?;
+        app.resize(Rect::new(0, 1, 20, 10))?;
+        app.cursor = Some((10, 10));
+        app.insert_pattern(&glider);
+        // a still life which must not disturb the camera
+        app.world
+            .paste(&Pattern::from_rle("x = 2, y = 2\n2o$2o!")?, 30, 10);
+        app.selection = Some(Selection {
+            start: (10, 10),
+            end: (12, 12),
+        });
+        app.toggle_follow();
+        assert!(matches!(app.camera, Camera::FollowObject(_)));
+        let start = (app.rendering_ix, app.rendering_iy);
+
+        app.toggle();
+        for _ in 0..40 {
+            app.tick();
+        }
+        assert!(matches!(app.camera, Camera::FollowObject(_)));
+        assert_eq!(
+            (app.rendering_ix - start.0, app.rendering_iy - start.1),
+            (10, 10)
+        );
+
+        app.toggle_follow();
+        assert_eq!(app.camera, Camera::Free);
+        Ok(())
+    }
+}
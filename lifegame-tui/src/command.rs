@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+/// Changes of the world and the view of [`App`].
+///
+/// Every change flows through [`App::apply`] so that a recorded session replays exactly.
+///
+/// [`App`]: crate::app::App
+/// [`App::apply`]: crate::app::App::apply
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Command {
+    /// Advance a generation if running
+    Tick,
+    /// Run/pause lifegame
+    Toggle,
+    /// Reset lifegame with the next random soup
+    Reset,
+    /// Pan rendering area, stopping the camera
+    Pan { dx: isize, dy: isize },
+    /// Pan rendering area to the cell, stopping the camera
+    Jump { ix: usize, iy: usize },
+    /// Pan rendering area to the center of the world
+    CenterWorld,
+    /// Pan rendering area to the center of the alive cells
+    FitPattern,
+    /// Resize the world to the rendering area
+    FitWorld,
+    /// The world is rendered in a new terminal area
+    Resize {
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    },
+    /// Move the cursor to the cell, clearing the selection
+    Click { ix: usize, iy: usize },
+    /// Select a rectangle between two corners
+    Select {
+        start: (usize, usize),
+        end: (usize, usize),
+    },
+    /// Replace the world with the RLE pattern at its center
+    LoadPattern { rle: String },
+    /// Paste the RLE pattern at the cursor
+    InsertPattern { rle: String },
+    /// Start/stop following the selected object or all alive cells
+    ToggleFollow,
+    /// Show/hide the minimap
+    ToggleMinimap,
+    /// Switch to the next color theme
+    CycleTheme,
+    /// Enable/disable coloring cells by their age
+    ToggleAgeColoring,
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use lifegame_core::Rule;
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::app::AppResult;
+use crate::keymap::{Action, KeyBinding};
+use crate::theme::Theme;
+
+/// Application configuration read from `$XDG_CONFIG_HOME/lifegame-tui/config.toml`.
+///
+/// Every field is optional and falls back to the default.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// initial world settings
+    pub world: WorldConfig,
+    /// rendering settings
+    pub ui: UiConfig,
+    /// image export settings
+    pub export: ExportConfig,
+    /// keys bound to each action, replacing the default keys of listed actions
+    pub keymap: HashMap<Action, Vec<KeyBinding>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorldConfig {
+    /// world size along with x-axis
+    pub nx: usize,
+    /// world size along with y-axis
+    pub ny: usize,
+    /// alive cell probability for random-generated initial map
+    pub alive_prob: f64,
+    /// resize the world to the terminal whenever the terminal is resized
+    pub fit_to_terminal: bool,
+    /// seed of random soups, chosen randomly if not given
+    pub seed: Option<u64>,
+    /// rule like `B3/S23`, or Generations rules like `/2/3`
+    pub rule: Rule,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            nx: 120,
+            ny: 60,
+            alive_prob: 0.2,
+            fit_to_terminal: false,
+            seed: None,
+            rule: Rule::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// tick rate in milliseconds
+    pub tick_rate: u64,
+    /// name of the built-in color theme
+    pub theme: String,
+    /// color cells by their age
+    pub age_coloring: bool,
+    /// show the minimap when the world is larger than the terminal
+    pub minimap: bool,
+    /// background color of alive cells, overriding the theme
+    pub alive_color: Option<Color>,
+    /// background color of dead cells, overriding the theme
+    pub dead_color: Option<Color>,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            tick_rate: 100,
+            theme: Theme::default().name.to_string(),
+            age_coloring: false,
+            minimap: true,
+            alive_color: None,
+            dead_color: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExportConfig {
+    /// pixels per cell
+    pub cell_size: u32,
+    /// number of generations in animations
+    pub generations: usize,
+    /// delay between animation frames in milliseconds
+    pub delay: u16,
+    /// color of grid lines, or no grid lines if not given
+    pub grid_color: Option<Color>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            cell_size: 4,
+            generations: 100,
+            delay: 100,
+            grid_color: None,
+        }
+    }
+}
+
+impl Config {
+    /// Path of the configuration file
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("lifegame-tui").join("config.toml"))
+    }
+
+    /// Load the configuration file, or the default configuration if there is no file.
+    pub fn load() -> AppResult<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => {
+                let text = fs::read_to_string(&path)?;
+                Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e).into())
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Parse configuration from TOML text
+    pub fn parse(text: &str) -> AppResult<Self> {
+        let config: Self = toml::from_str(text)?;
+        if config.world.nx < 3 || config.world.ny < 3 {
+            return Err("world size must be 3 or larger.".into());
+        }
+        if !(0.0..=1.0).contains(&config.world.alive_prob) {
+            return Err("alive_prob must be between 0 and 1.".into());
+        }
+        if config.ui.tick_rate == 0 {
+            return Err("tick_rate must be 1 or larger.".into());
+        }
+        if config.export.cell_size == 0 {
+            return Err("cell_size must be 1 or larger.".into());
+        }
+        if Theme::find(&config.ui.theme).is_none() {
+            return Err(format!("unknown theme '{}'.", config.ui.theme).into());
+        }
+        Ok(config)
+    }
+
+    /// The color theme with the color overrides applied
+    pub fn theme(&self) -> Theme {
+        let mut theme = Theme::find(&self.ui.theme).unwrap_or_default();
+        if let Some(color) = self.ui.alive_color {
+            theme.alive = color;
+        }
+        if let Some(color) = self.ui.dead_color {
+            theme.dead = color;
+        }
+        theme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r##"
+            [world]
+            nx = 200
+            alive_prob = 0.3
+            seed = 42
+            rule = "345/2/4"
+
+            [ui]
+            theme = "ocean"
+            alive_color = "#00ff00"
+
+            [keymap]
+            toggle = ["space", "s"]
+            pan_left = ["h", "left"]
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.world.nx, 200);
+        assert_eq!(config.world.ny, 60);
+        assert_eq!(config.world.alive_prob, 0.3);
+        assert_eq!(config.world.seed, Some(42));
+        assert_eq!(config.world.rule.to_string(), "B2/S345/C4");
+        assert_eq!(config.ui.tick_rate, 100);
+        assert_eq!(config.theme().name, "ocean");
+        assert_eq!(config.theme().alive, Color::Rgb(0, 255, 0));
+        assert_eq!(config.theme().dead, Theme::find("ocean").unwrap().dead);
+        assert_eq!(config.keymap[&Action::Toggle].len(), 2);
+        assert_eq!(config.keymap[&Action::PanLeft][0], "h".parse().unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid_config() {
+        assert!(Config::parse("[world]\nnx = 1").is_err());
+        assert!(Config::parse("[world]\nalive_prob = 2.0").is_err());
+        assert!(Config::parse("[world]\nrule = \"B9/S\"").is_err());
+        assert!(Config::parse("[keymap]\nfly = [\"f\"]").is_err());
+        assert!(Config::parse("[keymap]\nquit = [\"hyper-q\"]").is_err());
+        assert!(Config::parse("[ui]\ntheme = \"neon\"").is_err());
+        assert!(Config::parse("[ui]\ntick_rate = 0").is_err());
+        assert!(Config::parse("[export]\ncell_size = 0").is_err());
+        assert!(Config::parse("[unknown]").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use lifegame_core::library::{self, LibraryEntry};
+
+/// File extensions listed in the file browser
+pub const PATTERN_EXTENSIONS: &[&str] = &["rle", "cells", "txt", "mc"];
+
+/// Popup dialogs shown over the world.
+#[derive(Debug)]
+pub enum Popup {
+    /// Browse a pattern file to open
+    Open(FileBrowser),
+    /// Enter a path to save the world or the selection
+    Save(SavePrompt),
+    /// Search the built-in pattern library
+    Library(LibraryBrowser),
+    /// Enter a path to export an animation of the world or the selection
+    Export(SavePrompt),
+    /// Enter a path to export an image of the world or the selection
+    Snapshot(SavePrompt),
+}
+
+/// Entry of the file browser.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// displayed name
+    pub name: String,
+    /// full path
+    pub path: PathBuf,
+    /// whether the entry is a directory
+    pub is_dir: bool,
+}
+
+/// File browser listing directories and pattern files.
+#[derive(Debug)]
+pub struct FileBrowser {
+    /// current directory
+    pub dir: PathBuf,
+    /// entries in the current directory
+    pub entries: Vec<Entry>,
+    /// index of the selected entry
+    pub selected: usize,
+}
+
+impl FileBrowser {
+    /// Constructs a new instance of [`FileBrowser`].
+    pub fn new(dir: &Path) -> io::Result<Self> {
+        let dir = dir.canonicalize()?;
+        let entries = read_entries(&dir)?;
+        Ok(Self {
+            dir,
+            entries,
+            selected: 0,
+        })
+    }
+
+    /// Select the previous entry
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Select the next entry
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Enter the selected directory, or return the selected file.
+    pub fn enter(&mut self) -> io::Result<Option<PathBuf>> {
+        let Some(entry) = self.entries.get(self.selected) else {
+            return Ok(None);
+        };
+        if !entry.is_dir {
+            return Ok(Some(entry.path.clone()));
+        }
+        let dir = entry.path.clone();
+        *self = Self::new(&dir)?;
+        Ok(None)
+    }
+
+    /// Go to the parent directory
+    pub fn leave(&mut self) -> io::Result<()> {
+        if let Some(parent) = self.dir.parent().map(Path::to_path_buf) {
+            *self = Self::new(&parent)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_entries(dir: &Path) -> io::Result<Vec<Entry>> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            dirs.push(Entry {
+                name: format!("{}/", name),
+                path,
+                is_dir: true,
+            });
+        } else if is_pattern_file(&path) {
+            files.push(Entry {
+                name,
+                path,
+                is_dir: false,
+            });
+        }
+    }
+    dirs.sort_by(|a, b| a.name.cmp(&b.name));
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut entries = Vec::with_capacity(dirs.len() + files.len() + 1);
+    if let Some(parent) = dir.parent() {
+        entries.push(Entry {
+            name: "../".to_string(),
+            path: parent.to_path_buf(),
+            is_dir: true,
+        });
+    }
+    entries.extend(dirs);
+    entries.extend(files);
+    Ok(entries)
+}
+
+fn is_pattern_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| PATTERN_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Single line text input for the save or export path.
+#[derive(Debug)]
+pub struct SavePrompt {
+    /// entered path
+    pub input: String,
+}
+
+impl SavePrompt {
+    /// Constructs a new instance of [`SavePrompt`].
+    pub fn new(input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn pop(&mut self) {
+        self.input.pop();
+    }
+}
+
+/// Searchable list of the built-in pattern library.
+#[derive(Debug, Default)]
+pub struct LibraryBrowser {
+    /// search query
+    pub query: String,
+    /// index of the selected entry among the search results
+    pub selected: usize,
+}
+
+impl LibraryBrowser {
+    /// Library entries matching the query
+    pub fn entries(&self) -> Vec<&'static LibraryEntry> {
+        library::search(&self.query).collect()
+    }
+
+    /// The selected library entry
+    pub fn selected_entry(&self) -> Option<&'static LibraryEntry> {
+        self.entries().get(self.selected).copied()
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    /// Select the previous entry
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Select the next entry
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.entries().len().saturating_sub(1));
+    }
+}
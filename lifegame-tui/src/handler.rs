@@ -1,45 +1,219 @@
+use std::path::PathBuf;
+
 use crate::app::{App, AppResult};
-use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::command::Command;
+use crate::dialog::Popup;
+use crate::keymap::Action;
+use crate::ui;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
-    match key_event.code {
-        // Exit application on `ESC` or `q`
-        KeyCode::Esc | KeyCode::Char('q') => {
+    if app.popup.is_some() {
+        handle_popup_key_events(key_event, app);
+        return Ok(());
+    }
+    let Some(action) = app.keymap.action(key_event) else {
+        return Ok(());
+    };
+    match action {
+        // Exit application
+        Action::Quit => {
             app.quit();
         }
-        // Exit application on `Ctrl-C`
-        KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.quit();
-            }
-        }
         // Run/Pause lifegame
-        KeyCode::Char('s') => {
-            app.toggle();
+        Action::Toggle => {
+            app.apply(Command::Toggle)?;
         }
         // Reset lifegame
-        KeyCode::Char('r') => {
-            app.reset()?;
+        Action::Reset => {
+            app.apply(Command::Reset)?;
         }
         // Pan rendering area to left
-        KeyCode::Left => {
-            app.rendering_ix = app.rendering_ix.saturating_sub(1);
+        Action::PanLeft => {
+            app.apply(Command::Pan { dx: -1, dy: 0 })?;
         }
         // Pan rendering area to right
-        KeyCode::Right => {
-            app.rendering_ix = app.rendering_ix.saturating_add(1);
+        Action::PanRight => {
+            app.apply(Command::Pan { dx: 1, dy: 0 })?;
         }
-        // Pan rendering area to left
-        KeyCode::Up => {
-            app.rendering_iy = app.rendering_iy.saturating_sub(1);
+        // Pan rendering area to up
+        Action::PanUp => {
+            app.apply(Command::Pan { dx: 0, dy: -1 })?;
         }
-        // Pan rendering area to right
-        KeyCode::Down => {
-            app.rendering_iy = app.rendering_iy.saturating_add(1);
+        // Pan rendering area to down
+        Action::PanDown => {
+            app.apply(Command::Pan { dx: 0, dy: 1 })?;
+        }
+        // Switch color theme
+        Action::CycleTheme => {
+            app.apply(Command::CycleTheme)?;
+        }
+        // Enable/disable age coloring
+        Action::ToggleAgeColoring => {
+            app.apply(Command::ToggleAgeColoring)?;
+        }
+        // Open a pattern file
+        Action::Open => {
+            app.show_open_dialog();
+        }
+        // Save the world or the selection
+        Action::Save => {
+            app.show_save_dialog();
+        }
+        // Browse the pattern library
+        Action::Library => {
+            app.show_library();
+        }
+        // Center the world
+        Action::CenterWorld => {
+            app.apply(Command::CenterWorld)?;
+        }
+        // Center the alive cells
+        Action::FitPattern => {
+            app.apply(Command::FitPattern)?;
+        }
+        // Resize the world to the terminal
+        Action::FitWorld => {
+            app.apply(Command::FitWorld)?;
+        }
+        // Show/hide the minimap
+        Action::ToggleMinimap => {
+            app.apply(Command::ToggleMinimap)?;
+        }
+        // Follow the selected object or all alive cells
+        Action::ToggleFollow => {
+            app.apply(Command::ToggleFollow)?;
+        }
+        // Export an animation
+        Action::Export => {
+            app.show_export_dialog();
+        }
+        // Export an image
+        Action::Snapshot => {
+            app.show_snapshot_dialog();
+        }
+    }
+    Ok(())
+}
+
+/// Handles the key events while a popup dialog is shown.
+///
+/// Errors are shown in the status line and keep the application running.
+fn handle_popup_key_events(key_event: KeyEvent, app: &mut App) {
+    match (&mut app.popup, key_event.code) {
+        // Close the dialog
+        (_, KeyCode::Esc) => {
+            app.popup = None;
+        }
+        (Some(Popup::Open(browser)), KeyCode::Up) => browser.select_previous(),
+        (Some(Popup::Open(browser)), KeyCode::Down) => browser.select_next(),
+        (Some(Popup::Open(browser)), KeyCode::Backspace | KeyCode::Left) => {
+            if let Err(e) = browser.leave() {
+                app.set_status(format!("cannot list files: {}", e));
+            }
+        }
+        (Some(Popup::Open(browser)), KeyCode::Enter | KeyCode::Right) => match browser.enter() {
+            Ok(Some(path)) => {
+                app.popup = None;
+                match app.open_pattern(&path) {
+                    Ok(()) => app.set_status(format!("opened {}", path.display())),
+                    Err(e) => app.set_status(format!("cannot open {}: {}", path.display(), e)),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => app.set_status(format!("cannot list files: {}", e)),
+        },
+        (Some(Popup::Save(prompt)), KeyCode::Char(c)) => prompt.push(c),
+        (Some(Popup::Save(prompt)), KeyCode::Backspace) => prompt.pop(),
+        (Some(Popup::Save(prompt)), KeyCode::Enter) => {
+            let path = PathBuf::from(&prompt.input);
+            app.popup = None;
+            match app.save_pattern(&path) {
+                Ok(()) => app.set_status(format!("saved {}", path.display())),
+                Err(e) => app.set_status(format!("cannot save {}: {}", path.display(), e)),
+            }
+        }
+        (Some(Popup::Export(prompt)), KeyCode::Char(c)) => prompt.push(c),
+        (Some(Popup::Export(prompt)), KeyCode::Backspace) => prompt.pop(),
+        (Some(Popup::Export(prompt)), KeyCode::Enter) => {
+            let path = PathBuf::from(&prompt.input);
+            app.popup = None;
+            match app.export_animation(&path) {
+                Ok(()) => app.set_status(format!("exported {}", path.display())),
+                Err(e) => app.set_status(format!("cannot export {}: {}", path.display(), e)),
+            }
+        }
+        (Some(Popup::Snapshot(prompt)), KeyCode::Char(c)) => prompt.push(c),
+        (Some(Popup::Snapshot(prompt)), KeyCode::Backspace) => prompt.pop(),
+        (Some(Popup::Snapshot(prompt)), KeyCode::Enter) => {
+            let path = PathBuf::from(&prompt.input);
+            app.popup = None;
+            match app.export_snapshot(&path) {
+                Ok(()) => app.set_status(format!("exported {}", path.display())),
+                Err(e) => app.set_status(format!("cannot export {}: {}", path.display(), e)),
+            }
+        }
+        (Some(Popup::Library(browser)), KeyCode::Up) => browser.select_previous(),
+        (Some(Popup::Library(browser)), KeyCode::Down) => browser.select_next(),
+        (Some(Popup::Library(browser)), KeyCode::Char(c)) => browser.push(c),
+        (Some(Popup::Library(browser)), KeyCode::Backspace) => browser.pop(),
+        (Some(Popup::Library(browser)), KeyCode::Enter) => {
+            if let Some(entry) = browser.selected_entry() {
+                app.popup = None;
+                let rle = entry.pattern().to_rle();
+                if let Err(e) = app.apply(Command::InsertPattern { rle }) {
+                    app.set_status(format!("cannot insert {}: {}", entry.name, e));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Handles the mouse events and updates the state of [`App`].
+///
+/// Clicking a cell moves the cursor, and dragging selects a rectangle.
+/// Clicking or dragging on the minimap pans the rendering area there.
+pub fn handle_mouse_events(mouse_event: MouseEvent, app: &mut App) -> AppResult<()> {
+    if app.popup.is_some() {
+        return Ok(());
+    }
+    if matches!(
+        mouse_event.kind,
+        MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left)
+    ) && app.jump_by_minimap(mouse_event.column, mouse_event.row)?
+    {
+        return Ok(());
+    }
+    let Some(cell) = app.cell_at(mouse_event.column, mouse_event.row) else {
+        return Ok(());
+    };
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            app.apply(Command::Click {
+                ix: cell.0,
+                iy: cell.1,
+            })?;
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            if let Some(start) = app.cursor {
+                app.apply(Command::Select { start, end: cell })?;
+            }
         }
-        // Other handlers you could add here.
         _ => {}
     }
     Ok(())
 }
+
+/// Handles the terminal resize events and updates the state of [`App`].
+pub fn handle_resize_events(width: u16, height: u16, app: &mut App) -> AppResult<()> {
+    let area = ui::world_area(Rect::new(0, 0, width, height));
+    app.apply(Command::Resize {
+        x: area.x,
+        y: area.y,
+        width: area.width,
+        height: area.height,
+    })
+}
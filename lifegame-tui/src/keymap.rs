@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Application actions which can be bound to keys.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Quit application
+    Quit,
+    /// Run/pause lifegame
+    Toggle,
+    /// Reset lifegame
+    Reset,
+    /// Pan rendering area to left
+    PanLeft,
+    /// Pan rendering area to right
+    PanRight,
+    /// Pan rendering area to up
+    PanUp,
+    /// Pan rendering area to down
+    PanDown,
+    /// Switch to the next color theme
+    CycleTheme,
+    /// Enable/disable coloring cells by their age
+    ToggleAgeColoring,
+    /// Open a pattern file
+    Open,
+    /// Save the world or the selection to a pattern file
+    Save,
+    /// Browse the built-in pattern library
+    Library,
+    /// Pan rendering area to the center of the world
+    CenterWorld,
+    /// Pan rendering area to the center of the alive cells
+    FitPattern,
+    /// Resize the world to the terminal
+    FitWorld,
+    /// Show/hide the minimap
+    ToggleMinimap,
+    /// Start/stop following the selected object or all alive cells
+    ToggleFollow,
+    /// Export an animation of the world or the selection
+    Export,
+    /// Export an image of the world or the selection
+    Snapshot,
+}
+
+/// A key press with its modifiers, written like `q`, `space` or `ctrl-c`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(try_from = "String")]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // the case of a character already tells whether shift is pressed.
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for KeyBinding {
+    fn from(key_event: KeyEvent) -> Self {
+        Self::new(key_event.code, key_event.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = s;
+        while let Some((modifier, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty())
+        {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in key '{}'", modifier, s)),
+            };
+            key = rest;
+        }
+        let code = match key.to_lowercase().as_str() {
+            "esc" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            name => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => match modifiers.contains(KeyModifiers::SHIFT) {
+                        true => KeyCode::Char(c.to_ascii_uppercase()),
+                        false => KeyCode::Char(c),
+                    },
+                    _ => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                        _ => return Err(format!("unknown key '{}'", s)),
+                    },
+                }
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            code => f.write_str(&format!("{:?}", code).to_lowercase()),
+        }
+    }
+}
+
+/// Lookup table from key presses to actions.
+#[derive(Debug)]
+pub struct KeyMap {
+    bindings: HashMap<KeyBinding, Action>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = [
+            ("esc", Action::Quit),
+            ("q", Action::Quit),
+            ("ctrl-c", Action::Quit),
+            ("ctrl-C", Action::Quit),
+            ("s", Action::Toggle),
+            ("r", Action::Reset),
+            ("left", Action::PanLeft),
+            ("right", Action::PanRight),
+            ("up", Action::PanUp),
+            ("down", Action::PanDown),
+            ("t", Action::CycleTheme),
+            ("a", Action::ToggleAgeColoring),
+            ("o", Action::Open),
+            ("w", Action::Save),
+            ("p", Action::Library),
+            ("c", Action::CenterWorld),
+            ("z", Action::FitPattern),
+            ("F", Action::FitWorld),
+            ("m", Action::ToggleMinimap),
+            ("f", Action::ToggleFollow),
+            ("e", Action::Export),
+            ("i", Action::Snapshot),
+        ]
+        .into_iter()
+        .map(|(key, action)| (key.parse().expect("invalid default key"), action))
+        .collect();
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Create a keymap from the default one, replacing bindings of every given action.
+    pub fn with_overrides(overrides: &HashMap<Action, Vec<KeyBinding>>) -> Self {
+        let mut keymap = Self::default();
+        keymap
+            .bindings
+            .retain(|_, action| !overrides.contains_key(action));
+        for (action, keys) in overrides {
+            for key in keys {
+                keymap.bindings.insert(*key, *action);
+            }
+        }
+        keymap
+    }
+
+    /// Look up the action bound to the key event
+    pub fn action(&self, key_event: KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeyBinding::from(key_event)).copied()
+    }
+
+    /// Name of a key bound to the action, used for the help line
+    pub fn key_name(&self, action: Action) -> String {
+        let mut names = self
+            .bindings
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|(key, _)| key.to_string())
+            .collect::<Vec<_>>();
+        // prefer a short name so that it fits the help line.
+        names.sort_by_key(|name| (name.len(), name.clone()));
+        names.into_iter().next().unwrap_or_else(|| "-".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_binding() {
+        assert_eq!(
+            "q".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::Char('q'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "space".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "ctrl-c".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            "shift-g".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::Char('G'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "-".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::Char('-'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "F5".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::F(5), KeyModifiers::NONE))
+        );
+        assert!("hyper-x".parse::<KeyBinding>().is_err());
+        assert!("foo".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_key_binding_round_trip() {
+        for key in ["q", "space", "ctrl-c", "G", "left", "pagedown", "f1"] {
+            assert_eq!(key.parse::<KeyBinding>().unwrap().to_string(), key);
+        }
+    }
+
+    #[test]
+    fn test_keymap_overrides() {
+        let keymap = KeyMap::with_overrides(&HashMap::from([(
+            Action::Toggle,
+            vec!["space".parse().unwrap()],
+        )]));
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(keymap.action(key(KeyCode::Char(' '))), Some(Action::Toggle));
+        assert_eq!(keymap.action(key(KeyCode::Char('s'))), None);
+        assert_eq!(keymap.action(key(KeyCode::Char('q'))), Some(Action::Quit));
+        assert_eq!(
+            keymap.action(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(keymap.key_name(Action::Toggle), "space");
+    }
+}
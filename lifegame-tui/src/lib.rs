@@ -12,3 +12,24 @@ pub mod tui;
 
 /// Event handler.
 pub mod handler;
+
+/// Configuration file.
+pub mod config;
+
+/// Key bindings.
+pub mod keymap;
+
+/// Color themes.
+pub mod theme;
+
+/// Popup dialogs.
+pub mod dialog;
+
+/// World overview.
+pub mod minimap;
+
+/// Commands changing the application.
+pub mod command;
+
+/// Session recording and replay.
+pub mod record;
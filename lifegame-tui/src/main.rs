@@ -1,36 +1,151 @@
 use lifegame_tui::app::{App, AppResult, AppState};
+use lifegame_tui::command::Command;
+use lifegame_tui::config::Config;
 use lifegame_tui::event::{Event, EventHandler};
-use lifegame_tui::handler::handle_key_events;
+use lifegame_tui::handler::{handle_key_events, handle_mouse_events, handle_resize_events};
+use lifegame_tui::keymap::Action;
+use lifegame_tui::record::{Player, Recorder, Recording};
 use lifegame_tui::tui::Tui;
-use ratatui::backend::CrosstermBackend;
+use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::Terminal;
 use std::io;
+use std::path::PathBuf;
+
+/// Tick rate while replaying, fine enough to follow the recorded timing
+const REPLAY_TICK_RATE: u64 = 10;
+
+const USAGE: &str = "usage: lifegame-tui [--record FILE | --replay FILE [--headless]]";
+
+/// Command line options.
+#[derive(Debug, Default)]
+struct Args {
+    /// record the session into the file
+    record: Option<PathBuf>,
+    /// replay the recorded session from the file
+    replay: Option<PathBuf>,
+    /// replay without the terminal, printing the final world
+    headless: bool,
+}
+
+impl Args {
+    fn parse() -> AppResult<Self> {
+        let mut args = Self::default();
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--record" => args.record = Some(iter.next().ok_or(USAGE)?.into()),
+                "--replay" => args.replay = Some(iter.next().ok_or(USAGE)?.into()),
+                "--headless" => args.headless = true,
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ => return Err(USAGE.into()),
+            }
+        }
+        if (args.record.is_some() && args.replay.is_some())
+            || (args.headless && args.replay.is_none())
+        {
+            return Err(USAGE.into());
+        }
+        Ok(args)
+    }
+}
 
 fn main() -> AppResult<()> {
-    // Create an application.
-    let mut app = App::new();
+    let args = Args::parse()?;
+
+    // Load the configuration and create an application.
+    let config = Config::load()?;
+    let recording = args.replay.as_deref().map(Recording::load).transpose()?;
+    if let (Some(recording), true) = (&recording, args.headless) {
+        let app = recording.replay_headless(&config)?;
+        eprintln!(
+            "generation {}, population {}",
+            app.gen,
+            app.world.population()
+        );
+        print!("{}", app.world.extract(0, 0, app.nx, app.ny).to_rle());
+        return Ok(());
+    }
+    let (mut app, mut player) = match recording {
+        Some(recording) => {
+            let mut app = recording.initial_app(&config)?;
+            app.replaying = true;
+            (app, Some(recording.play()))
+        }
+        None => (App::with_config(&config), None),
+    };
+    if let Some(path) = &args.record {
+        app.recorder = Some(Recorder::create(path, &app)?);
+    }
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(100);
+    let tick_rate = match player {
+        Some(_) => REPLAY_TICK_RATE,
+        None => config.ui.tick_rate,
+    };
+    let events = EventHandler::new(tick_rate);
     let mut tui = Tui::new(terminal, events);
     tui.init()?;
 
-    // Start the main loop.
+    // Start the main loop, restoring the terminal even if it fails.
+    let result = run(&mut tui, &mut app, player.as_mut());
+
+    // Exit the user interface.
+    tui.exit()?;
+    result?;
+    if let Some(recorder) = &mut app.recorder {
+        recorder.flush()?;
+    }
+    Ok(())
+}
+
+/// Draw the app and handle events until quitting
+fn run<B: Backend>(
+    tui: &mut Tui<B>,
+    app: &mut App,
+    mut player: Option<&mut Player>,
+) -> AppResult<()> {
+    if player.is_none() {
+        // the world area of the initial terminal size; later sizes come as resize events
+        let size = tui.size()?;
+        handle_resize_events(size.width, size.height, app)?;
+    }
     while app.state != AppState::Quit {
         // Render the user interface.
-        tui.draw(&mut app)?;
+        tui.draw(app)?;
         // Handle events.
-        match tui.events.next()? {
-            Event::Tick => app.tick(),
-            Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
-            Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
+        match (&mut player, tui.events.next()?) {
+            (Some(player), Event::Tick) => replay(player, app)?,
+            // Only quitting is allowed while replaying.
+            (Some(_), Event::Key(key_event)) => {
+                if app.keymap.action(key_event) == Some(Action::Quit) {
+                    app.quit();
+                }
+            }
+            (Some(_), _) => {}
+            (None, Event::Tick) => app.apply(Command::Tick)?,
+            (None, Event::Key(key_event)) => handle_key_events(key_event, app)?,
+            (None, Event::Mouse(mouse_event)) => handle_mouse_events(mouse_event, app)?,
+            (None, Event::Resize(width, height)) => handle_resize_events(width, height, app)?,
         }
     }
+    Ok(())
+}
 
-    // Exit the user interface.
-    tui.exit()?;
+/// Apply the recorded commands which are due
+fn replay(player: &mut Player, app: &mut App) -> AppResult<()> {
+    if player.is_finished() {
+        return Ok(());
+    }
+    for command in player.due() {
+        app.apply(command)?;
+    }
+    if player.is_finished() {
+        app.set_status("replay finished");
+    }
     Ok(())
 }
@@ -0,0 +1,116 @@
+use std::ops::Range;
+
+use lifegame_core::{World, CELL_ALIVE};
+
+/// Characters shading the density of alive cells, from empty to full
+pub const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Downsampled overview of the whole world.
+///
+/// Every minimap cell covers `scale` x `scale` cells of the world.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Minimap {
+    /// world cells per minimap cell along with each axis
+    pub scale: usize,
+    /// minimap size along with x-axis
+    pub width: usize,
+    /// minimap size along with y-axis
+    pub height: usize,
+    nx: usize,
+    ny: usize,
+}
+
+impl Minimap {
+    /// Constructs a new instance of [`Minimap`] fitting the world into the maximum size.
+    pub fn new(nx: usize, ny: usize, max_width: usize, max_height: usize) -> Self {
+        let scale = nx
+            .div_ceil(max_width.max(1))
+            .max(ny.div_ceil(max_height.max(1)))
+            .max(1);
+        Self {
+            scale,
+            width: nx.div_ceil(scale),
+            height: ny.div_ceil(scale),
+            nx,
+            ny,
+        }
+    }
+
+    /// World cells covered by the minimap cell
+    pub fn cell_range(&self, mx: usize, my: usize) -> (Range<usize>, Range<usize>) {
+        (
+            (mx * self.scale).min(self.nx)..((mx + 1) * self.scale).min(self.nx),
+            (my * self.scale).min(self.ny)..((my + 1) * self.scale).min(self.ny),
+        )
+    }
+
+    /// Minimap cell covering the world cell
+    pub fn to_minimap(&self, ix: usize, iy: usize) -> (usize, usize) {
+        (
+            (ix / self.scale).min(self.width.saturating_sub(1)),
+            (iy / self.scale).min(self.height.saturating_sub(1)),
+        )
+    }
+
+    /// World cell at the center of the minimap cell
+    pub fn to_world(&self, mx: usize, my: usize) -> (usize, usize) {
+        let (xs, ys) = self.cell_range(mx, my);
+        ((xs.start + xs.end) / 2, (ys.start + ys.end) / 2)
+    }
+
+    /// Shade of the minimap cell by the ratio of alive cells
+    pub fn shade(&self, world: &World, mx: usize, my: usize) -> char {
+        let (xs, ys) = self.cell_range(mx, my);
+        let total = xs.len() * ys.len();
+        if total == 0 {
+            return SHADES[0];
+        }
+        let alive = ys
+            .flat_map(|iy| xs.clone().map(move |ix| (ix, iy)))
+            .filter(|&(ix, iy)| world.get_present_cell(ix, iy) == CELL_ALIVE)
+            .count();
+        // any alive cell is visible, and only a full block is shaded fully.
+        let index = match alive {
+            0 => 0,
+            n if n == total => SHADES.len() - 1,
+            n => (1 + n * (SHADES.len() - 2) / total).min(SHADES.len() - 2),
+        };
+        SHADES[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lifegame_core::CELL_DEAD;
+
+    #[test]
+    fn test_minimap_geometry() {
+        let minimap = Minimap::new(120, 60, 30, 10);
+        assert_eq!(minimap.scale, 6);
+        assert_eq!((minimap.width, minimap.height), (20, 10));
+        assert_eq!(minimap.cell_range(1, 9), (6..12, 54..60));
+        assert_eq!(minimap.to_minimap(13, 59), (2, 9));
+        assert_eq!(minimap.to_world(2, 0), (15, 3));
+
+        // small worlds are not magnified
+        let minimap = Minimap::new(10, 5, 30, 10);
+        assert_eq!((minimap.scale, minimap.width, minimap.height), (1, 10, 5));
+    }
+
+    #[test]
+    fn test_minimap_shade() {
+        let mut world = World::new(6, 6, &[CELL_DEAD; 36]).unwrap();
+        world.set_present_cell(1, 1, CELL_ALIVE);
+        for (ix, iy) in [(3, 1), (4, 1), (3, 2), (4, 2)] {
+            world.set_present_cell(ix, iy, CELL_ALIVE);
+        }
+        let minimap = Minimap::new(6, 6, 3, 3);
+        assert_eq!(minimap.scale, 2);
+        assert_eq!(minimap.shade(&world, 0, 0), SHADES[1]);
+        assert_eq!(minimap.shade(&world, 2, 0), SHADES[1]);
+        assert_eq!(minimap.shade(&world, 0, 1), SHADES[0]);
+        let minimap = Minimap::new(6, 6, 6, 6);
+        assert_eq!(minimap.shade(&world, 3, 1), SHADES[4]);
+    }
+}
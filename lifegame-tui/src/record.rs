@@ -0,0 +1,275 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use lifegame_core::{Pattern, Rule, World};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{App, AppResult};
+use crate::command::Command;
+use crate::config::Config;
+
+/// Version of the recording file format
+const VERSION: u32 = 1;
+
+/// First line of a recording, holding the state when the recording started.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    /// file format version
+    pub version: u32,
+    /// seed of random soups
+    pub seed: u64,
+    /// number of resets so far, deciding the seed of the next soup
+    pub resets: u64,
+    /// alive cell probability of random soups
+    pub alive_prob: f64,
+    /// whether the world is resized to the terminal
+    pub fit_to_terminal: bool,
+    /// the whole world including the boundary in RLE with the rule
+    pub world: String,
+}
+
+/// A command applied at the time since the recording started.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// milliseconds since the recording started
+    pub t: u64,
+    /// applied command
+    #[serde(flatten)]
+    pub command: Command,
+}
+
+/// Writes the commands applied to [`App`] into a file, one JSON object per line.
+#[derive(Debug)]
+pub struct Recorder {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    /// Create a recording file starting from the present state of the application.
+    pub fn create(path: &Path, app: &App) -> AppResult<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header = Header {
+            version: VERSION,
+            seed: app.seed,
+            resets: app.resets,
+            alive_prob: app.alive_prob,
+            fit_to_terminal: app.fit_to_terminal,
+            world: Pattern {
+                rule: Some(app.world.rule().to_definition()),
+                ..app.world.extract(0, 0, app.nx, app.ny)
+            }
+            .to_rle(),
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        Ok(Self {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    /// Append a command
+    pub fn log(&mut self, command: &Command) -> AppResult<()> {
+        let entry = Entry {
+            t: self.start.elapsed().as_millis() as u64,
+            command: command.clone(),
+        };
+        serde_json::to_writer(&mut self.writer, &entry)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> AppResult<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A recorded session.
+#[derive(Debug)]
+pub struct Recording {
+    pub header: Header,
+    pub entries: Vec<Entry>,
+}
+
+impl Recording {
+    /// Load a recording file
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)?,
+            None => return Err("empty recording.".into()),
+        };
+        if header.version != VERSION {
+            return Err(format!("unsupported recording version {}.", header.version).into());
+        }
+        let mut entries = Vec::new();
+        for line in lines {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self { header, entries })
+    }
+
+    /// Create the application in the state when the recording started
+    pub fn initial_app(&self, config: &Config) -> AppResult<App> {
+        let pattern = Pattern::from_rle(&self.header.world)?;
+        let mut app = App::with_config(config);
+        let rule = match &pattern.rule {
+            Some(rule) => rule.parse()?,
+            None => Rule::default(),
+        };
+        app.world = World::new(pattern.width, pattern.height, &pattern.cells)?.with_rule(rule);
+        app.nx = pattern.width;
+        app.ny = pattern.height;
+        app.seed = self.header.seed;
+        app.resets = self.header.resets;
+        app.alive_prob = self.header.alive_prob;
+        app.fit_to_terminal = self.header.fit_to_terminal;
+        Ok(app)
+    }
+
+    /// Apply all the commands without a terminal, ignoring their timing.
+    pub fn replay_headless(&self, config: &Config) -> AppResult<App> {
+        let mut app = self.initial_app(config)?;
+        for entry in &self.entries {
+            app.apply(entry.command.clone())?;
+        }
+        Ok(app)
+    }
+
+    /// Start replaying the commands in real time
+    pub fn play(self) -> Player {
+        Player {
+            entries: self.entries,
+            next: 0,
+            start: Instant::now(),
+        }
+    }
+}
+
+/// Replays recorded commands at their recorded timing.
+#[derive(Debug)]
+pub struct Player {
+    entries: Vec<Entry>,
+    next: usize,
+    start: Instant,
+}
+
+impl Player {
+    /// Commands which are due by now
+    pub fn due(&mut self) -> Vec<Command> {
+        let elapsed = self.start.elapsed();
+        let mut commands = Vec::new();
+        while let Some(entry) = self.entries.get(self.next) {
+            if Duration::from_millis(entry.t) > elapsed {
+                break;
+            }
+            commands.push(entry.command.clone());
+            self.next += 1;
+        }
+        commands
+    }
+
+    /// Check if all the commands have been replayed
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AppState;
+
+    #[test]
+    fn test_record_and_replay() -> AppResult<()> {
+        let config = Config::default();
+        let path =
+            std::env::temp_dir().join(format!("lifegame-tui-test-{}.rec", std::process::id()));
+        let mut app = App::with_config(&config);
+        app.recorder = Some(Recorder::create(&path, &app)?);
+        for command in [
+            Command::Resize {
+                x: 0,
+                y: 1,
+                width: 80,
+                height: 20,
+            },
+            Command::Toggle,
+            Command::Tick,
+            Command::Tick,
+            Command::Toggle,
+            Command::Click { ix: 5, iy: 6 },
+            Command::InsertPattern {
+                rle: "x = 3, y = 3\nbo$2bo$3o!".to_string(),
+            },
+            Command::Reset,
+            Command::Pan { dx: 7, dy: 3 },
+            Command::Toggle,
+            Command::Tick,
+            Command::Tick,
+            Command::Tick,
+        ] {
+            app.apply(command)?;
+        }
+        app.recorder.take().unwrap().flush()?;
+
+        let recording = Recording::load(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(recording.entries.len(), 13);
+
+        let replayed = recording.replay_headless(&config)?;
+        assert_eq!(replayed.state, AppState::Run);
+        assert_eq!(replayed.gen, app.gen);
+        assert_eq!(replayed.resets, 1);
+        assert_eq!(
+            (replayed.rendering_ix, replayed.rendering_iy),
+            (app.rendering_ix, app.rendering_iy)
+        );
+        assert_eq!(
+            replayed.world.extract(0, 0, replayed.nx, replayed.ny),
+            app.world.extract(0, 0, app.nx, app.ny)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_rule_table() -> AppResult<()> {
+        let table = "@RULE Turn\n@TABLE\nn_states:3\nneighborhood:vonNeumann\n\
+                     symmetries:none\n0,1,0,0,0,2\n2,0,0,0,0,1\n";
+        let config = Config::default();
+        let path = std::env::temp_dir().join(format!(
+            "lifegame-tui-test-table-{}.rec",
+            std::process::id()
+        ));
+        let mut app = App::with_config(&config);
+        app.world.set_rule(table.parse()?);
+        app.world.set_present_cell(2, 2, 1);
+        Recorder::create(&path, &app)?.flush()?;
+
+        let recording = Recording::load(&path)?;
+        std::fs::remove_file(&path)?;
+        let replayed = recording.initial_app(&config)?;
+        assert_eq!(replayed.world.rule(), app.world.rule());
+        assert_eq!(replayed.world.get_present_cell(2, 2), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_entry_format() -> AppResult<()> {
+        let entry = Entry {
+            t: 42,
+            command: Command::Pan { dx: -1, dy: 0 },
+        };
+        let json = serde_json::to_string(&entry)?;
+        assert_eq!(json, r#"{"t":42,"type":"pan","dx":-1,"dy":0}"#);
+        assert_eq!(serde_json::from_str::<Entry>(&json)?, entry);
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+use lifegame_core::{Age, Cell, CELL_ALIVE, CELL_DEAD};
+use lifegame_export::style::Rgb;
+use lifegame_export::Style;
+use ratatui::style::Color;
+
+/// Color theme of the world.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    /// theme name used in the configuration file
+    pub name: &'static str,
+    /// background color of alive cells
+    pub alive: Color,
+    /// background color of dead cells
+    pub dead: Color,
+    /// colors of alive cells on age coloring, from newborn to old.
+    /// `ages[i]` is used for cells aged `2^i - 1` or older.
+    pub ages: &'static [Color],
+    /// colors of recently-dead cells on age coloring, fading out
+    pub trail: &'static [Color],
+    /// colors of dying cells of Generations rules, from just died to nearly dead
+    pub dying: &'static [Color],
+}
+
+/// Built-in themes. The first one is the default.
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "classic",
+        alive: Color::Blue,
+        dead: Color::Reset,
+        ages: &[
+            Color::LightCyan,
+            Color::LightBlue,
+            Color::Blue,
+            Color::Magenta,
+            Color::Red,
+        ],
+        trail: &[Color::DarkGray],
+        dying: &[Color::LightMagenta, Color::Magenta, Color::DarkGray],
+    },
+    Theme {
+        name: "mono",
+        alive: Color::White,
+        dead: Color::Black,
+        ages: &[
+            Color::Rgb(255, 255, 255),
+            Color::Rgb(208, 208, 208),
+            Color::Rgb(168, 168, 168),
+            Color::Rgb(128, 128, 128),
+        ],
+        trail: &[
+            Color::Rgb(64, 64, 64),
+            Color::Rgb(40, 40, 40),
+            Color::Rgb(20, 20, 20),
+        ],
+        dying: &[
+            Color::Rgb(176, 176, 176),
+            Color::Rgb(112, 112, 112),
+            Color::Rgb(56, 56, 56),
+        ],
+    },
+    Theme {
+        name: "ocean",
+        alive: Color::Rgb(0, 160, 200),
+        dead: Color::Rgb(0, 16, 40),
+        ages: &[
+            Color::Rgb(200, 255, 255),
+            Color::Rgb(100, 220, 240),
+            Color::Rgb(0, 160, 200),
+            Color::Rgb(0, 100, 180),
+            Color::Rgb(40, 40, 160),
+        ],
+        trail: &[
+            Color::Rgb(0, 64, 96),
+            Color::Rgb(0, 40, 72),
+            Color::Rgb(0, 28, 56),
+        ],
+        dying: &[
+            Color::Rgb(0, 120, 160),
+            Color::Rgb(0, 84, 128),
+            Color::Rgb(0, 52, 96),
+        ],
+    },
+    Theme {
+        name: "fire",
+        alive: Color::Rgb(255, 120, 0),
+        dead: Color::Rgb(16, 0, 0),
+        ages: &[
+            Color::Rgb(255, 255, 160),
+            Color::Rgb(255, 220, 0),
+            Color::Rgb(255, 120, 0),
+            Color::Rgb(220, 40, 0),
+            Color::Rgb(140, 0, 0),
+        ],
+        trail: &[
+            Color::Rgb(96, 24, 0),
+            Color::Rgb(64, 12, 0),
+            Color::Rgb(40, 4, 0),
+        ],
+        dying: &[
+            Color::Rgb(200, 64, 0),
+            Color::Rgb(144, 32, 0),
+            Color::Rgb(88, 12, 0),
+        ],
+    },
+    Theme {
+        name: "matrix",
+        alive: Color::Green,
+        dead: Color::Black,
+        ages: &[
+            Color::Rgb(200, 255, 200),
+            Color::Rgb(0, 255, 64),
+            Color::Rgb(0, 200, 40),
+            Color::Rgb(0, 140, 20),
+        ],
+        trail: &[Color::Rgb(0, 72, 0), Color::Rgb(0, 40, 0)],
+        dying: &[
+            Color::Rgb(0, 160, 0),
+            Color::Rgb(0, 104, 0),
+            Color::Rgb(0, 56, 0),
+        ],
+    },
+];
+
+impl Default for Theme {
+    fn default() -> Self {
+        THEMES[0]
+    }
+}
+
+impl Theme {
+    /// Find a built-in theme by name
+    pub fn find(name: &str) -> Option<Self> {
+        THEMES.iter().find(|theme| theme.name == name).copied()
+    }
+
+    /// The built-in theme following this one
+    pub fn next(&self) -> Self {
+        let index = THEMES
+            .iter()
+            .position(|theme| theme.name == self.name)
+            .map_or(0, |index| (index + 1) % THEMES.len());
+        THEMES[index]
+    }
+
+    /// Colors of alive and dead cells in exported images,
+    /// falling back to the default for colors of the terminal
+    pub fn export_style(&self, style: Style) -> Style {
+        Style {
+            alive: rgb(self.alive).unwrap_or(style.alive),
+            dead: rgb(self.dead).unwrap_or(style.dead),
+            ..style
+        }
+    }
+
+    /// Background color of a cell of the rule with the number of states
+    pub fn cell_color(&self, cell: Cell, states: u8, age: Age, age_coloring: bool) -> Color {
+        match (cell, age_coloring) {
+            (CELL_ALIVE, false) => self.alive,
+            (CELL_ALIVE, true) => {
+                let index = (age as u32 + 1).ilog2() as usize;
+                self.ages[index.min(self.ages.len() - 1)]
+            }
+            (CELL_DEAD, true) if (age as usize) < self.trail.len() => self.trail[age as usize],
+            (CELL_DEAD, _) => self.dead,
+            _ => {
+                // the dying states 2..states spread over the colors
+                let dying_states = states.saturating_sub(2).max(1) as usize;
+                let state = (cell as usize - 2).min(dying_states - 1);
+                self.dying[state * self.dying.len() / dying_states]
+            }
+        }
+    }
+}
+
+/// RGB value of a color, or `None` for the default colors of the terminal.
+/// Named colors take the values of the xterm palette.
+pub fn rgb(color: Color) -> Option<Rgb> {
+    let rgb = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => [r, g, b],
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 0, 0],
+        Color::Green => [0, 205, 0],
+        Color::Yellow => [205, 205, 0],
+        Color::Blue => [0, 0, 238],
+        Color::Magenta => [205, 0, 205],
+        Color::Cyan => [0, 205, 205],
+        Color::Gray => [229, 229, 229],
+        Color::DarkGray => [127, 127, 127],
+        Color::LightRed => [255, 0, 0],
+        Color::LightGreen => [0, 255, 0],
+        Color::LightYellow => [255, 255, 0],
+        Color::LightBlue => [92, 92, 255],
+        Color::LightMagenta => [255, 0, 255],
+        Color::LightCyan => [0, 255, 255],
+        Color::White => [255, 255, 255],
+        Color::Indexed(_) => return None,
+    };
+    Some(rgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lifegame_core::AGE_NEVER_ALIVE;
+
+    #[test]
+    fn test_cell_color() {
+        let theme = Theme::find("classic").unwrap();
+        assert_eq!(theme.cell_color(CELL_ALIVE, 2, 100, false), theme.alive);
+        assert_eq!(theme.cell_color(CELL_DEAD, 2, 0, false), theme.dead);
+
+        assert_eq!(theme.cell_color(CELL_ALIVE, 2, 0, true), theme.ages[0]);
+        assert_eq!(theme.cell_color(CELL_ALIVE, 2, 1, true), theme.ages[1]);
+        assert_eq!(theme.cell_color(CELL_ALIVE, 2, 2, true), theme.ages[1]);
+        assert_eq!(theme.cell_color(CELL_ALIVE, 2, 3, true), theme.ages[2]);
+        assert_eq!(
+            theme.cell_color(CELL_ALIVE, 2, Age::MAX, true),
+            theme.ages[theme.ages.len() - 1]
+        );
+        assert_eq!(theme.cell_color(CELL_DEAD, 2, 0, true), theme.trail[0]);
+        assert_eq!(
+            theme.cell_color(CELL_DEAD, 2, AGE_NEVER_ALIVE, true),
+            theme.dead
+        );
+    }
+
+    #[test]
+    fn test_dying_color() {
+        let theme = Theme::find("classic").unwrap();
+        // Brian's Brain has 1 dying state, and Star Wars 2
+        assert_eq!(theme.cell_color(2, 3, 0, false), theme.dying[0]);
+        assert_eq!(theme.cell_color(2, 4, 0, true), theme.dying[0]);
+        assert_eq!(theme.cell_color(3, 4, 0, false), theme.dying[1]);
+        assert_eq!(theme.cell_color(2, 255, 0, false), theme.dying[0]);
+        assert_eq!(theme.cell_color(254, 255, 0, false), theme.dying[2]);
+        // left from a rule with more states
+        assert_eq!(theme.cell_color(9, 4, 0, false), theme.dying[1]);
+        assert_eq!(theme.cell_color(2, 2, 0, false), theme.dying[0]);
+    }
+
+    #[test]
+    fn test_export_style() {
+        let style = Theme::find("classic")
+            .unwrap()
+            .export_style(Style::default());
+        assert_eq!(style.alive, [0, 0, 238]);
+        assert_eq!(style.dead, Style::default().dead);
+        let style = Theme::find("ocean").unwrap().export_style(Style::default());
+        assert_eq!((style.alive, style.dead), ([0, 160, 200], [0, 16, 40]));
+    }
+
+    #[test]
+    fn test_cycle_themes() {
+        let mut theme = Theme::default();
+        for _ in 0..THEMES.len() {
+            theme = theme.next();
+        }
+        assert_eq!(theme, Theme::default());
+    }
+}
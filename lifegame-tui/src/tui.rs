@@ -4,6 +4,7 @@ use crate::ui;
 use ratatui::backend::Backend;
 use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::Rect;
 use ratatui::Terminal;
 use std::io;
 use std::panic;
@@ -50,11 +51,16 @@ impl<B: Backend> Tui<B> {
     ///
     /// [`Draw`]: ratatui::Terminal::draw
     /// [`rendering`]: crate::ui::render
-    pub fn draw(&mut self, app: &mut App) -> AppResult<()> {
+    pub fn draw(&mut self, app: &App) -> AppResult<()> {
         self.terminal.draw(|frame| ui::render(app, frame))?;
         Ok(())
     }
 
+    /// Size of the terminal
+    pub fn size(&self) -> AppResult<Rect> {
+        Ok(self.terminal.size()?)
+    }
+
     /// Resets the terminal interface.
     ///
     /// This function is also used for the panic hook to revert
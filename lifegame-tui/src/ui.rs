@@ -1,44 +1,193 @@
 use std::cmp::min;
 
-use lifegame_core::CELL_ALIVE;
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    widgets::{Block, Cell, Row, Table, Widget},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Clear, List, ListState, Paragraph, Row, Table, Widget, Wrap},
     Frame,
 };
 
-use crate::app::{App, AppState};
+use crate::app::{App, AppState, Camera};
+use crate::dialog::{FileBrowser, LibraryBrowser, Popup, SavePrompt};
+use crate::keymap::Action;
+use crate::minimap::Minimap;
 
 /// Renders the user interface widgets.
-pub fn render(app: &mut App, frame: &mut Frame) {
+pub fn render(app: &App, frame: &mut Frame) {
     // This is where you add new widgets.
     // See the following resources:
     // - https://docs.rs/ratatui/latest/ratatui/widgets/index.html
     // - https://github.com/ratatui-org/ratatui/tree/master/examples
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Max(1), Constraint::Min(1)])
-        .split(frame.size());
-    let (description, world) = (layout[0], layout[1]);
+    let [description, world, status] = layout(frame.size());
+    let key = |action| app.keymap.key_name(action);
     frame.render_widget(
         Block::default().title(format!(
-            "Lifegame (gen={}) {}{}[<q>: quit]",
+            "Lifegame (gen={}, rule={}) {}{}[<{}>: center] [<{}>: fit] [<{}>: map] [<{}>: follow] [<{}>: open] [<{}>: save] [<{}>: export] [<{}>: image] [<{}>: library] [<{}>: theme] [<{}>: age] [<{}>: quit]",
             app.gen,
+            app.world.rule(),
             if app.state == AppState::Pause {
-                "[<s>: start] "
+                format!("[<{}>: start] ", key(Action::Toggle))
             } else {
-                "[<s>: pause] "
+                format!("[<{}>: pause] ", key(Action::Toggle))
             },
             if app.can_reset() {
-                "[<left><up><down><right>: pan] [<r>: reset] "
+                format!(
+                    "[<{}><{}><{}><{}>: pan] [<{}>: reset] ",
+                    key(Action::PanLeft),
+                    key(Action::PanUp),
+                    key(Action::PanDown),
+                    key(Action::PanRight),
+                    key(Action::Reset)
+                )
             } else {
-                ""
-            }
+                "".to_string()
+            },
+            key(Action::CenterWorld),
+            key(Action::FitPattern),
+            key(Action::ToggleMinimap),
+            key(Action::ToggleFollow),
+            key(Action::Open),
+            key(Action::Save),
+            key(Action::Export),
+            key(Action::Snapshot),
+            key(Action::Library),
+            key(Action::CycleTheme),
+            key(Action::ToggleAgeColoring),
+            key(Action::Quit)
         )),
         description,
     );
     frame.render_widget(TableWorld::new(app, world.width, world.height), world);
+    if let Some(minimap) = app.minimap() {
+        let area = app.minimap_area();
+        frame.render_widget(Clear, area);
+        frame.render_widget(MinimapWidget { app, minimap }, area);
+    }
+    frame.render_widget(Paragraph::new(status_line(app)), status);
+    let target = match app.selection {
+        Some(_) => "selection",
+        None => "world",
+    };
+    match &app.popup {
+        Some(Popup::Open(browser)) => render_file_browser(browser, frame),
+        Some(Popup::Save(prompt)) => {
+            let title = format!("Save {} to (.rle or .cells)", target);
+            render_path_prompt(prompt, &title, "save", frame)
+        }
+        Some(Popup::Library(browser)) => render_library(browser, frame),
+        Some(Popup::Export(prompt)) => {
+            let title = format!(
+                "Export {} generations of {} to (.gif or .png)",
+                app.export.generations, target
+            );
+            render_path_prompt(prompt, &title, "export", frame)
+        }
+        Some(Popup::Snapshot(prompt)) => {
+            let title = format!("Export image of {} to (.png or .svg)", target);
+            render_path_prompt(prompt, &title, "export", frame)
+        }
+        None => {}
+    }
+}
+
+/// Split the terminal into the description, world and status areas.
+fn layout(area: Rect) -> [Rect; 3] {
+    Layout::vertical([Constraint::Max(1), Constraint::Min(1), Constraint::Max(1)]).areas(area)
+}
+
+/// The area where the world is rendered in the terminal
+pub fn world_area(area: Rect) -> Rect {
+    layout(area)[1]
+}
+
+fn status_line(app: &App) -> String {
+    let mut items = Vec::new();
+    if let Some((ix, iy)) = app.cursor {
+        items.push(format!("cursor=({}, {})", ix, iy));
+    }
+    if let Some(selection) = app.selection {
+        let (_, _, width, height) = selection.rect();
+        items.push(format!("selection={}x{}", width, height));
+    }
+    match app.camera {
+        Camera::Free => {}
+        Camera::FollowAll => items.push("camera=follow all".to_string()),
+        Camera::FollowObject(_) => items.push("camera=follow object".to_string()),
+    }
+    if let Some(status) = &app.status {
+        items.push(status.clone());
+    }
+    items.join(" ")
+}
+
+/// Centered area of the given size, clipped to the frame
+fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+fn render_file_browser(browser: &FileBrowser, frame: &mut Frame) {
+    let area = popup_area(frame.size(), 60, 20);
+    let list = List::new(browser.entries.iter().map(|entry| entry.name.as_str()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Open {}", browser.dir.display()))
+                .title_bottom(
+                    "[<up><down>: select] [<enter>: open] [<backspace>: parent] [<esc>: cancel]",
+                ),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(browser.selected));
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_library(browser: &LibraryBrowser, frame: &mut Frame) {
+    let area = popup_area(frame.size(), 70, 24);
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Library search: {}_", browser.query))
+        .title_bottom("[<up><down>: select] [<enter>: insert at cursor] [<esc>: cancel]");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [list_area, description_area] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(inner);
+    let entries = browser.entries();
+    let list = List::new(
+        entries
+            .iter()
+            .map(|entry| format!("{:<24} {}", entry.name, entry.category.name())),
+    )
+    .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(browser.selected));
+    frame.render_stateful_widget(list, list_area, &mut state);
+    if let Some(entry) = entries.get(browser.selected) {
+        frame.render_widget(
+            Paragraph::new(entry.description).wrap(Wrap { trim: true }),
+            description_area,
+        );
+    }
+}
+
+/// Render a prompt of the path to save or export to
+fn render_path_prompt(prompt: &SavePrompt, title: &str, action: &str, frame: &mut Frame) {
+    let area = popup_area(frame.size(), 60, 3);
+    let paragraph = Paragraph::new(format!("{}_", prompt.input)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(format!("[<enter>: {}] [<esc>: cancel]", action)),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
 }
 
 struct TableWorld<'a> {
@@ -55,8 +204,9 @@ impl<'a> TableWorld<'a> {
         Self { app, width, height }
     }
 
-    fn make_rows(&self) -> Vec<Row> {
+    fn make_rows(&self) -> Vec<Row<'_>> {
         let mut rows: Vec<Row> = Vec::with_capacity(self.height as usize);
+        let states = self.app.world.rule().states();
         for iy in
             self.app.rendering_iy..min(self.app.ny, self.app.rendering_iy + self.height as usize)
         {
@@ -64,10 +214,20 @@ impl<'a> TableWorld<'a> {
             for ix in
                 self.app.rendering_ix..min(self.app.nx, self.app.rendering_ix + self.width as usize)
             {
-                row.push(match self.app.world.get_present_cell(ix, iy) {
-                    CELL_ALIVE => Cell::from(" ").style(Style::default().bg(Color::Blue)),
-                    _ => Cell::from(" ").style(Style::default()),
-                });
+                let color = self.app.theme.cell_color(
+                    self.app.world.get_present_cell(ix, iy),
+                    states,
+                    self.app.world.get_present_age(ix, iy),
+                    self.app.age_coloring,
+                );
+                let symbol = if self.app.cursor == Some((ix, iy)) {
+                    "+"
+                } else if self.app.selection.is_some_and(|s| s.contains(ix, iy)) {
+                    "·"
+                } else {
+                    " "
+                };
+                row.push(Cell::from(symbol).style(Style::default().fg(Color::Yellow).bg(color)));
             }
             rows.push(Row::new(row));
         }
@@ -93,3 +253,44 @@ impl Widget for TableWorld<'_> {
             .render(area, buf);
     }
 }
+
+struct MinimapWidget<'a> {
+    /// application status
+    app: &'a App,
+    /// minimap geometry
+    minimap: Minimap,
+}
+
+impl Widget for MinimapWidget<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let block = Block::default().borders(Borders::ALL).title("map");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // the rendering area is outlined.
+        let world_area = self.app.world_area;
+        let (x0, y0) = self
+            .minimap
+            .to_minimap(self.app.rendering_ix, self.app.rendering_iy);
+        let (x1, y1) = self.minimap.to_minimap(
+            (self.app.rendering_ix + world_area.width as usize).saturating_sub(1),
+            (self.app.rendering_iy + world_area.height as usize).saturating_sub(1),
+        );
+        for my in 0..self.minimap.height.min(inner.height as usize) {
+            for mx in 0..self.minimap.width.min(inner.width as usize) {
+                let on_outline = ((mx == x0 || mx == x1) && (y0..=y1).contains(&my))
+                    || ((my == y0 || my == y1) && (x0..=x1).contains(&mx));
+                let style = match on_outline {
+                    true => Style::default().fg(Color::Black).bg(Color::Yellow),
+                    false => Style::default().fg(self.app.theme.alive),
+                };
+                buf.get_mut(inner.x + mx as u16, inner.y + my as u16)
+                    .set_char(self.minimap.shade(&self.app.world, mx, my))
+                    .set_style(style);
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! WebAssembly binding of lifegame-core for static web pages.
+//!
+//! Build with `wasm-pack build --target web lifegame-wasm`.
+
+use lifegame_core::{Cell, Pattern, Rule, CELL_ALIVE, CELL_DEAD};
+use wasm_bindgen::prelude::*;
+
+/// Game of life world, whose size includes the always-dead boundary.
+#[wasm_bindgen]
+pub struct World {
+    world: lifegame_core::World,
+}
+
+#[wasm_bindgen]
+impl World {
+    /// Create a world of dead cells
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Result<World, JsError> {
+        Self::from_cells(width, height, &vec![CELL_DEAD; width * height])
+    }
+
+    /// Create a world of the cell states in row-major order, 1 for alive and 0 for dead
+    #[wasm_bindgen(js_name = fromCells)]
+    pub fn from_cells(width: usize, height: usize, cells: &[Cell]) -> Result<World, JsError> {
+        let world = lifegame_core::World::new(width, height, cells).map_err(js_error)?;
+        Ok(World { world })
+    }
+
+    /// Create a world of a RLE, plaintext or macrocell pattern with a margin of dead cells
+    #[wasm_bindgen(js_name = fromPattern)]
+    pub fn from_pattern(text: &str, margin: usize) -> Result<World, JsError> {
+        let pattern = Pattern::parse(text).map_err(js_error)?;
+        let rule = match &pattern.rule {
+            Some(rule) => rule.parse::<Rule>().map_err(js_error)?,
+            None => Rule::default(),
+        };
+        // the boundary on both sides
+        let (width, height) = (
+            pattern.width + 2 * margin + 2,
+            pattern.height + 2 * margin + 2,
+        );
+        let mut world = World::new(width, height)?;
+        world.world.set_rule(rule);
+        world.world.paste(&pattern, margin + 1, margin + 1);
+        Ok(world)
+    }
+
+    /// Width including the boundary
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.world.nx()
+    }
+
+    /// Height including the boundary
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.world.ny()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn generation(&self) -> usize {
+        self.world.generation()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn population(&self) -> usize {
+        self.world.population()
+    }
+
+    /// Rule like `B3/S23`
+    #[wasm_bindgen(getter)]
+    pub fn rule(&self) -> String {
+        self.world.rule().to_string()
+    }
+
+    #[wasm_bindgen(js_name = setRule)]
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsError> {
+        self.world.set_rule(rule.parse().map_err(js_error)?);
+        Ok(())
+    }
+
+    /// Advance the world by the number of generations
+    pub fn step(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.world.next();
+        }
+    }
+
+    /// Whether the cell is alive, false for dying cells of Generations rules and out of the world
+    #[wasm_bindgen(js_name = isAlive)]
+    pub fn is_alive(&self, ix: usize, iy: usize) -> bool {
+        ix < self.world.nx()
+            && iy < self.world.ny()
+            && self.world.get_present_cell(ix, iy) == CELL_ALIVE
+    }
+
+    /// Set the cell alive or dead. Cells on the boundary stay dead.
+    #[wasm_bindgen(js_name = setAlive)]
+    pub fn set_alive(&mut self, ix: usize, iy: usize, alive: bool) {
+        let cell = if alive { CELL_ALIVE } else { CELL_DEAD };
+        self.world.set_present_cell(ix, iy, cell);
+    }
+
+    /// Pointer to the present cells in the wasm memory, `width * height` bytes in row-major order
+    /// of 0 for dead, 1 for alive and 2 or more for dying cells of Generations rules.
+    ///
+    /// The cells are viewed without copying as `new Uint8Array(memory().buffer, cellsPtr(), width * height)`.
+    /// The pointer changes on every step, and the view must be created again after stepping.
+    #[wasm_bindgen(js_name = cellsPtr)]
+    pub fn cells_ptr(&self) -> *const Cell {
+        self.world.present_cells().as_ptr()
+    }
+
+    /// Copy of the present cells in row-major order
+    pub fn cells(&self) -> Vec<Cell> {
+        self.world.present_cells().to_vec()
+    }
+
+    /// The world without the boundary as RLE
+    #[wasm_bindgen(js_name = toRle)]
+    pub fn to_rle(&self) -> String {
+        let (width, height) = (
+            self.world.nx().saturating_sub(2),
+            self.world.ny().saturating_sub(2),
+        );
+        let mut pattern = self.world.extract(1, 1, width, height);
+        pattern.rule = Some(self.world.rule().to_definition());
+        pattern.to_rle()
+    }
+}
+
+/// Memory of the wasm module, which the cell buffer lives in
+#[wasm_bindgen]
+pub fn memory() -> JsValue {
+    wasm_bindgen::memory()
+}
+
+fn js_error(e: anyhow::Error) -> JsError {
+    JsError::new(&e.to_string())
+}
@@ -0,0 +1,67 @@
+//! Run with `wasm-pack test --node` or `cargo test --target wasm32-unknown-unknown`.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Uint8Array, WebAssembly};
+use lifegame_wasm::{memory, World};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+const GLIDER: &str = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+
+/// View of the cells without copying, as JS renderers do
+fn view(world: &World) -> Vec<u8> {
+    let buffer = memory().unchecked_into::<WebAssembly::Memory>().buffer();
+    let length = (world.width() * world.height()) as u32;
+    Uint8Array::new_with_byte_offset_and_length(&buffer, world.cells_ptr() as u32, length).to_vec()
+}
+
+#[wasm_bindgen_test]
+fn create_world() {
+    let world = World::new(8, 6).unwrap();
+    assert_eq!((world.width(), world.height()), (8, 6));
+    assert_eq!((world.generation(), world.population()), (0, 0));
+    assert_eq!(world.rule(), "B3/S23");
+    assert!(World::from_cells(3, 3, &[0; 8]).is_err());
+
+    let world = World::from_pattern(GLIDER, 2).unwrap();
+    assert_eq!((world.width(), world.height()), (9, 9));
+    assert_eq!(world.population(), 5);
+    assert!(world.is_alive(4, 3));
+    assert!(!world.is_alive(3, 3));
+    assert!(!world.is_alive(100, 100));
+    assert!(World::from_pattern("x = 1, y = 1\n2o!", 0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn step_and_edit() {
+    let mut world = World::new(6, 5).unwrap();
+    for ix in 1..4 {
+        world.set_alive(ix, 2, true);
+    }
+    // cells on the boundary stay dead.
+    world.set_alive(0, 0, true);
+    assert_eq!(world.population(), 3);
+
+    world.step(1);
+    assert_eq!(world.generation(), 1);
+    assert!(world.is_alive(2, 1) && world.is_alive(2, 2) && world.is_alive(2, 3));
+    assert!(!world.is_alive(1, 2));
+
+    world.set_rule("B36/S23").unwrap();
+    assert_eq!(world.rule(), "B36/S23");
+    assert!(world.set_rule("B9/S").is_err());
+}
+
+#[wasm_bindgen_test]
+fn zero_copy_view() {
+    let mut world = World::from_pattern(GLIDER, 1).unwrap();
+    assert_eq!(view(&world), world.cells());
+    world.step(4);
+    // the view follows the present buffer after stepping.
+    assert_eq!(view(&world), world.cells());
+    assert_eq!(world.population(), 5);
+    assert_eq!(
+        world.to_rle(),
+        "x = 5, y = 5, rule = B3/S23\n2$3bo$4bo$2b3o!\n"
+    );
+}
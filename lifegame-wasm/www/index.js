@@ -0,0 +1,41 @@
+// Serve lifegame-wasm after `wasm-pack build --target web`, e.g. `python3 -m http.server` in lifegame-wasm.
+import init, { World, memory } from "../pkg/lifegame_wasm.js";
+
+/** Pixels per cell */
+const CELL_SIZE = 4
+
+const GOSPER_GLIDER_GUN = `x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!`
+
+await init()
+const world = World.fromPattern(GOSPER_GLIDER_GUN, 40)
+const canvas = document.getElementById("world")
+canvas.width = world.width * CELL_SIZE
+canvas.height = world.height * CELL_SIZE
+const context = canvas.getContext("2d")
+const status = document.getElementById("status")
+
+function draw() {
+  // the cells are read from the wasm memory without copying
+  const cells = new Uint8Array(memory().buffer, world.cellsPtr(), world.width * world.height)
+  context.fillStyle = "#ffffff"
+  context.fillRect(0, 0, canvas.width, canvas.height)
+  context.fillStyle = "#2050d0"
+  for (let iy = 0; iy < world.height; iy++) {
+    for (let ix = 0; ix < world.width; ix++) {
+      if (cells[world.width * iy + ix] !== 0) {
+        context.fillRect(ix * CELL_SIZE, iy * CELL_SIZE, CELL_SIZE, CELL_SIZE)
+      }
+    }
+  }
+  status.textContent = `gen=${world.generation} population=${world.population}`
+}
+
+function loop() {
+  world.step(1)
+  draw()
+  requestAnimationFrame(loop)
+}
+draw()
+requestAnimationFrame(loop)